@@ -0,0 +1,592 @@
+// Color capabilities of the terminal and conversions between RGB, the
+// xterm 256-color palette and the 16 ANSI colors.
+use std::io::IsTerminal;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use colored::Color;
+
+use crate::termprint::{get_terminal_type, index2rgb};
+
+// How long to wait for the terminal to answer the background color query
+const QUERY_TIMEOUT: Duration = Duration::from_millis(100);
+
+// "light" or "dark", overrides the detected background
+pub const BACKGROUND_ENV: &str = "TERMPRINT_BACKGROUND";
+
+static BACKGROUND: OnceLock<Background> = OnceLock::new();
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ColorSupport {
+    Basic,
+    Ansi256,
+    TrueColor,
+}
+
+// From COLORTERM ("truecolor", "24bit") and TERM ("*-256color").
+pub fn detect_color_support() -> ColorSupport {
+    let colorterm = std::env::var("COLORTERM").unwrap_or_default();
+    if colorterm == "truecolor" || colorterm == "24bit" {
+        ColorSupport::TrueColor
+    } else if get_terminal_type().contains("256color") {
+        ColorSupport::Ansi256
+    } else {
+        ColorSupport::Basic
+    }
+}
+
+// Weighted ("redmean") RGB distance: cheap, but close to how far apart two
+// colors look, unlike the plain euclidean distance which overrates blue.
+pub fn color_distance(a: (u8, u8, u8), b: (u8, u8, u8)) -> f64 {
+    let mean = (a.0 as f64 + b.0 as f64) / 2.0;
+    let dr = a.0 as f64 - b.0 as f64;
+    let dg = a.1 as f64 - b.1 as f64;
+    let db = a.2 as f64 - b.2 as f64;
+    (2.0 + mean / 256.0) * dr * dr + 4.0 * dg * dg + (2.0 + (255.0 - mean) / 256.0) * db * db
+}
+
+fn nearest(rgb: (u8, u8, u8), indices: std::ops::Range<usize>) -> usize {
+    let start = indices.start;
+    indices
+        .min_by(|&i, &j| {
+            color_distance(rgb, index2rgb(i)).total_cmp(&color_distance(rgb, index2rgb(j)))
+        })
+        .unwrap_or(start)
+}
+
+// Closest entry of the xterm 256-color cube (16-231) or grayscale ramp
+// (232-255). The first 16 entries are skipped as terminals remap them.
+pub fn rgb2index(r: u8, g: u8, b: u8) -> usize {
+    nearest((r, g, b), 16..256)
+}
+
+// Closest of the 16 ANSI colors (0-7 normal, 8-15 bright) in xterm's palette.
+pub fn rgb2ansi16(r: u8, g: u8, b: u8) -> usize {
+    nearest((r, g, b), 0..16)
+}
+
+// ANSI color index 0-15 as a colored Color, out of range is white.
+pub fn ansi16_color(index: usize) -> Color {
+    match index {
+        0 => Color::Black,
+        1 => Color::Red,
+        2 => Color::Green,
+        3 => Color::Yellow,
+        4 => Color::Blue,
+        5 => Color::Magenta,
+        6 => Color::Cyan,
+        7 => Color::White,
+        8 => Color::BrightBlack,
+        9 => Color::BrightRed,
+        10 => Color::BrightGreen,
+        11 => Color::BrightYellow,
+        12 => Color::BrightBlue,
+        13 => Color::BrightMagenta,
+        14 => Color::BrightCyan,
+        _ => Color::BrightWhite,
+    }
+}
+
+// Foreground SGR parameters of an RGB color reduced to the given support,
+// e.g. "38;5;33" for Ansi256.
+pub fn fg_code(r: u8, g: u8, b: u8, support: ColorSupport) -> String {
+    match support {
+        ColorSupport::TrueColor => format!("38;2;{};{};{}", r, g, b),
+        ColorSupport::Ansi256 => format!("38;5;{}", rgb2index(r, g, b)),
+        ColorSupport::Basic => match rgb2ansi16(r, g, b) {
+            i @ 0..=7 => (30 + i).to_string(),
+            i => (90 + i - 8).to_string(),
+        },
+    }
+}
+
+// Background a color is shown on, for contrast checks
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Background {
+    #[default]
+    Dark,
+    Light,
+}
+
+impl Background {
+    pub fn rgb(&self) -> (u8, u8, u8) {
+        match self {
+            Background::Dark => (0, 0, 0),
+            Background::Light => (255, 255, 255),
+        }
+    }
+
+    // Light when black text on it has more contrast than white text
+    pub fn from_rgb(rgb: (u8, u8, u8)) -> Self {
+        if contrast_ratio(rgb, (0, 0, 0)) > contrast_ratio(rgb, (255, 255, 255)) {
+            Background::Light
+        } else {
+            Background::Dark
+        }
+    }
+}
+
+// Background of the terminal from TERMPRINT_BACKGROUND, else COLORFGBG,
+// else dark. Never talks to the terminal, so it is safe as a default.
+pub fn detect_background() -> Background {
+    env_background()
+        .or_else(colorfgbg_background)
+        .unwrap_or_default()
+}
+
+// Like detect_background, but asks the terminal with an OSC 11 query when
+// neither variable is set. Opt-in: the query switches /dev/tty to raw mode
+// for up to 100ms and is only sent by the foreground process. Answered once
+// per process.
+pub fn query_background() -> Background {
+    *BACKGROUND.get_or_init(|| {
+        env_background()
+            .or_else(|| query_background_color(QUERY_TIMEOUT).map(Background::from_rgb))
+            .or_else(colorfgbg_background)
+            .unwrap_or_default()
+    })
+}
+
+fn env_background() -> Option<Background> {
+    match std::env::var(BACKGROUND_ENV).ok()?.to_lowercase().as_str() {
+        "light" => Some(Background::Light),
+        "dark" => Some(Background::Dark),
+        _ => None,
+    }
+}
+
+// COLORFGBG is "fg;bg" (or "fg;default;bg") with ANSI color indices, set by
+// rxvt, Konsole and some others. Only white and bright white are light; the
+// other bright colors (blue, red, ...) are dark backgrounds.
+fn colorfgbg_background() -> Option<Background> {
+    let value = std::env::var("COLORFGBG").ok()?;
+    match value.rsplit(';').next()?.parse::<u8>().ok()? {
+        7 | 15 => Some(Background::Light),
+        _ => Some(Background::Dark),
+    }
+}
+
+// Parses the "rgb:RRRR/GGGG/BBBB" answer, with 1 to 4 hex digits per channel.
+fn parse_osc_color(reply: &str) -> Option<(u8, u8, u8)> {
+    let start = reply.find("rgb:")? + 4;
+    let end = reply[start..]
+        .find(|c: char| !c.is_ascii_hexdigit() && c != '/')
+        .map_or(reply.len(), |i| start + i);
+    let channel = |hex: &str| -> Option<u8> {
+        if hex.is_empty() || hex.len() > 4 {
+            return None;
+        }
+        let max = (1u32 << (4 * hex.len())) - 1;
+        let value = u32::from_str_radix(hex, 16).ok()?;
+        Some((value * 255 / max) as u8)
+    };
+    let mut parts = reply[start..end].split('/').map(channel);
+    Some((parts.next()??, parts.next()??, parts.next()??))
+}
+
+// Asks the terminal for its background color (OSC 11) through /dev/tty in
+// raw mode. None if there is no terminal, the process runs in the background
+// (changing the terminal mode would stop it with SIGTTOU) or the terminal
+// does not answer in time.
+#[cfg(unix)]
+pub fn query_background_color(timeout: Duration) -> Option<(u8, u8, u8)> {
+    use std::fs::OpenOptions;
+    use std::io::{Read, Write};
+    use std::os::unix::io::AsRawFd;
+    use std::time::Instant;
+
+    if !std::io::stdout().is_terminal() || get_terminal_type() == "dumb" {
+        return None;
+    }
+    let mut tty = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open("/dev/tty")
+        .ok()?;
+    let fd = tty.as_raw_fd();
+    if unsafe { libc::tcgetpgrp(fd) != libc::getpgrp() } {
+        return None;
+    }
+
+    // SAFETY: termios is plain data filled in by tcgetattr on an open fd
+    let mut saved: libc::termios = unsafe { std::mem::zeroed() };
+    if unsafe { libc::tcgetattr(fd, &mut saved) } != 0 {
+        return None;
+    }
+    let mut raw = saved;
+    raw.c_lflag &= !(libc::ICANON | libc::ECHO);
+    raw.c_cc[libc::VMIN] = 0;
+    raw.c_cc[libc::VTIME] = 0;
+    if unsafe { libc::tcsetattr(fd, libc::TCSANOW, &raw) } != 0 {
+        return None;
+    }
+
+    let mut reply = Vec::new();
+    if tty
+        .write_all(b"\x1b]11;?\x1b\\")
+        .and_then(|_| tty.flush())
+        .is_ok()
+    {
+        let deadline = Instant::now() + timeout;
+        let mut buf = [0u8; 64];
+        // The answer ends with BEL or ST (ESC \)
+        while !reply.ends_with(b"\x07") && !reply.ends_with(b"\x1b\\") {
+            let left = deadline.saturating_duration_since(Instant::now());
+            if left.is_zero() {
+                break;
+            }
+            let mut pfd = libc::pollfd {
+                fd,
+                events: libc::POLLIN,
+                revents: 0,
+            };
+            if unsafe { libc::poll(&mut pfd, 1, left.as_millis() as libc::c_int) } <= 0 {
+                break;
+            }
+            match tty.read(&mut buf) {
+                Ok(n) if n > 0 => reply.extend_from_slice(&buf[..n]),
+                _ => break,
+            }
+        }
+    }
+    unsafe { libc::tcsetattr(fd, libc::TCSANOW, &saved) };
+    parse_osc_color(&String::from_utf8_lossy(&reply))
+}
+
+#[cfg(not(unix))]
+pub fn query_background_color(_timeout: Duration) -> Option<(u8, u8, u8)> {
+    None
+}
+
+fn to_linear(c: u8) -> f64 {
+    let c = c as f64 / 255.0;
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn from_linear(c: f64) -> u8 {
+    let c = if c <= 0.0031308 {
+        12.92 * c
+    } else {
+        1.055 * c.powf(1.0 / 2.4) - 0.055
+    };
+    (c.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+// Hue in degrees 0-360, saturation and lightness 0-1.
+pub fn rgb2hsl(r: u8, g: u8, b: u8) -> (f64, f64, f64) {
+    let (r, g, b) = (r as f64 / 255.0, g as f64 / 255.0, b as f64 / 255.0);
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let l = (max + min) / 2.0;
+    let d = max - min;
+    if d == 0.0 {
+        return (0.0, 0.0, l);
+    }
+    let s = d / (1.0 - (2.0 * l - 1.0).abs());
+    let h = if max == r {
+        60.0 * ((g - b) / d).rem_euclid(6.0)
+    } else if max == g {
+        60.0 * ((b - r) / d + 2.0)
+    } else {
+        60.0 * ((r - g) / d + 4.0)
+    };
+    (h, s, l)
+}
+
+pub fn hsl2rgb(h: f64, s: f64, l: f64) -> (u8, u8, u8) {
+    let (s, l) = (s.clamp(0.0, 1.0), l.clamp(0.0, 1.0));
+    let c = (1.0 - (2.0 * l - 1.0).abs()) * s;
+    let h = h.rem_euclid(360.0) / 60.0;
+    let x = c * (1.0 - (h % 2.0 - 1.0).abs());
+    let (r, g, b) = match h as u32 {
+        0 => (c, x, 0.0),
+        1 => (x, c, 0.0),
+        2 => (0.0, c, x),
+        3 => (0.0, x, c),
+        4 => (x, 0.0, c),
+        _ => (c, 0.0, x),
+    };
+    let m = l - c / 2.0;
+    let to_u8 = |v: f64| ((v + m).clamp(0.0, 1.0) * 255.0).round() as u8;
+    (to_u8(r), to_u8(g), to_u8(b))
+}
+
+fn rgb2oklab(r: u8, g: u8, b: u8) -> (f64, f64, f64) {
+    let (r, g, b) = (to_linear(r), to_linear(g), to_linear(b));
+    let l = (0.4122214708 * r + 0.5363325363 * g + 0.0514459929 * b).cbrt();
+    let m = (0.2119034982 * r + 0.6806995451 * g + 0.1073969566 * b).cbrt();
+    let s = (0.0883024619 * r + 0.2817188376 * g + 0.6299787005 * b).cbrt();
+    (
+        0.2104542553 * l + 0.7936177850 * m - 0.0040720468 * s,
+        1.9779984951 * l - 2.4285922050 * m + 0.4505937099 * s,
+        0.0259040371 * l + 0.7827717662 * m - 0.8086757660 * s,
+    )
+}
+
+// Linear sRGB, possibly outside 0-1 for colors out of gamut
+fn oklab2linear(l: f64, a: f64, b: f64) -> (f64, f64, f64) {
+    let l_ = (l + 0.3963377774 * a + 0.2158037573 * b).powi(3);
+    let m_ = (l - 0.1055613458 * a - 0.0638541728 * b).powi(3);
+    let s_ = (l - 0.0894841775 * a - 1.2914855480 * b).powi(3);
+    (
+        4.0767416621 * l_ - 3.3077115913 * m_ + 0.2309699292 * s_,
+        -1.2684380046 * l_ + 2.6097574011 * m_ - 0.3413193965 * s_,
+        -0.0041960863 * l_ - 0.7034186147 * m_ + 1.7076147010 * s_,
+    )
+}
+
+// Lightness 0-1, chroma (0 to about 0.37) and hue in degrees.
+pub fn rgb2oklch(r: u8, g: u8, b: u8) -> (f64, f64, f64) {
+    let (l, a, b) = rgb2oklab(r, g, b);
+    let c = (a * a + b * b).sqrt();
+    let h = if c < 1e-6 {
+        0.0
+    } else {
+        b.atan2(a).to_degrees().rem_euclid(360.0)
+    };
+    (l, c, h)
+}
+
+// Colors outside sRGB keep lightness and hue and lose chroma until they fit.
+pub fn oklch2rgb(l: f64, c: f64, h: f64) -> (u8, u8, u8) {
+    let l = l.clamp(0.0, 1.0);
+    let (sin, cos) = h.to_radians().sin_cos();
+    let linear = |c: f64| oklab2linear(l, c * cos, c * sin);
+    let in_gamut =
+        |(r, g, b): (f64, f64, f64)| [r, g, b].iter().all(|v| (-1e-4..=1.0 + 1e-4).contains(v));
+    let mut c = c.max(0.0);
+    if !in_gamut(linear(c)) {
+        let (mut lo, mut hi) = (0.0, c);
+        for _ in 0..20 {
+            let mid = (lo + hi) / 2.0;
+            if in_gamut(linear(mid)) {
+                lo = mid;
+            } else {
+                hi = mid;
+            }
+        }
+        c = lo;
+    }
+    let (r, g, b) = linear(c);
+    (from_linear(r), from_linear(g), from_linear(b))
+}
+
+pub fn lerp_rgb(a: (u8, u8, u8), b: (u8, u8, u8), t: f64) -> (u8, u8, u8) {
+    let t = t.clamp(0.0, 1.0);
+    let mix = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * t).round() as u8;
+    (mix(a.0, b.0), mix(a.1, b.1), mix(a.2, b.2))
+}
+
+// Color at t (0-1) of a gradient through evenly spaced stops.
+pub fn gradient_at(stops: &[(u8, u8, u8)], t: f64) -> (u8, u8, u8) {
+    match stops.len() {
+        0 => (0, 0, 0),
+        1 => stops[0],
+        n => {
+            let pos = t.clamp(0.0, 1.0) * (n - 1) as f64;
+            let i = (pos.floor() as usize).min(n - 2);
+            lerp_rgb(stops[i], stops[i + 1], pos - i as f64)
+        }
+    }
+}
+
+// n colors from the first to the last stop.
+pub fn gradient(stops: &[(u8, u8, u8)], n: usize) -> Vec<(u8, u8, u8)> {
+    match n {
+        0 => vec![],
+        1 => vec![gradient_at(stops, 0.0)],
+        _ => (0..n)
+            .map(|i| gradient_at(stops, i as f64 / (n - 1) as f64))
+            .collect(),
+    }
+}
+
+// WCAG relative luminance, 0 for black to 1 for white
+pub fn luminance(r: u8, g: u8, b: u8) -> f64 {
+    0.2126 * to_linear(r) + 0.7152 * to_linear(g) + 0.0722 * to_linear(b)
+}
+
+// WCAG contrast ratio from 1 (same luminance) to 21 (black on white).
+pub fn contrast_ratio(a: (u8, u8, u8), b: (u8, u8, u8)) -> f64 {
+    let la = luminance(a.0, a.1, a.2);
+    let lb = luminance(b.0, b.1, b.2);
+    (la.max(lb) + 0.05) / (la.min(lb) + 0.05)
+}
+
+// WCAG AA for normal text is 4.5, for large text and graphics 3.
+pub fn is_readable(color: (u8, u8, u8), background: Background, min_ratio: f64) -> bool {
+    contrast_ratio(color, background.rgb()) >= min_ratio
+}
+
+// Moves the OKLCH lightness away from the background until the contrast
+// ratio is reached, keeping the hue.
+pub fn ensure_contrast(
+    color: (u8, u8, u8),
+    background: Background,
+    min_ratio: f64,
+) -> (u8, u8, u8) {
+    let (mut l, c, h) = rgb2oklch(color.0, color.1, color.2);
+    let step = match background {
+        Background::Dark => 0.02,
+        Background::Light => -0.02,
+    };
+    let mut rgb = color;
+    while !is_readable(rgb, background, min_ratio) && (0.0..=1.0).contains(&l) {
+        l += step;
+        rgb = oklch2rgb(l, c, h);
+    }
+    rgb
+}
+
+// Golden angle between consecutive hues, so neighbors always differ a lot
+const GOLDEN_ANGLE: f64 = 137.507_764;
+// Lightness levels alternated once the hues get close
+const PALETTE_LIGHTNESS: [f64; 3] = [0.72, 0.6, 0.84];
+
+// n colors that are as distinct as possible: hues spread by the golden
+// angle at equal OKLCH lightness and chroma, so no color stands out, and
+// readable on the background (contrast of at least 3).
+pub fn palette(n: usize, background: Background) -> Vec<(u8, u8, u8)> {
+    (0..n)
+        .map(|i| {
+            let h = (30.0 + i as f64 * GOLDEN_ANGLE).rem_euclid(360.0);
+            let l = match background {
+                Background::Dark => PALETTE_LIGHTNESS[(i / 8) % 3],
+                Background::Light => 1.0 - PALETTE_LIGHTNESS[(i / 8) % 3] + 0.1,
+            };
+            ensure_contrast(oklch2rgb(l, 0.15, h), background, 3.0)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLES: [(u8, u8, u8); 8] = [
+        (0, 0, 0),
+        (255, 255, 255),
+        (128, 128, 128),
+        (255, 0, 0),
+        (51, 102, 255),
+        (12, 200, 90),
+        (250, 128, 114),
+        (1, 2, 3),
+    ];
+
+    #[test]
+    fn hsl_round_trips() {
+        for rgb in SAMPLES {
+            let (h, s, l) = rgb2hsl(rgb.0, rgb.1, rgb.2);
+            assert_eq!(hsl2rgb(h, s, l), rgb, "hsl {:?}", (h, s, l));
+        }
+        let (h, s, l) = rgb2hsl(51, 102, 255);
+        assert!((h - 225.0).abs() < 1e-9 && (s - 1.0).abs() < 1e-9 && (l - 0.6).abs() < 1e-9);
+        assert_eq!(hsl2rgb(-120.0, 1.0, 0.5), hsl2rgb(240.0, 1.0, 0.5));
+    }
+
+    #[test]
+    fn oklch_round_trips() {
+        for rgb in SAMPLES {
+            let (l, c, h) = rgb2oklch(rgb.0, rgb.1, rgb.2);
+            assert_eq!(oklch2rgb(l, c, h), rgb, "oklch {:?}", (l, c, h));
+        }
+        let (l, c, _) = rgb2oklch(255, 255, 255);
+        assert!((l - 1.0).abs() < 1e-4 && c < 1e-4);
+    }
+
+    #[test]
+    fn out_of_gamut_oklch_keeps_lightness() {
+        let rgb = oklch2rgb(0.7, 0.4, 150.0);
+        let (l, c, h) = rgb2oklch(rgb.0, rgb.1, rgb.2);
+        assert!((l - 0.7).abs() < 0.01, "{:?}", (l, c, h));
+        assert!((h - 150.0).abs() < 2.0, "{:?}", (l, c, h));
+        assert!(c < 0.4);
+    }
+
+    #[test]
+    fn wcag_contrast_ratio() {
+        assert!((contrast_ratio((0, 0, 0), (255, 255, 255)) - 21.0).abs() < 1e-9);
+        assert!((contrast_ratio((255, 255, 255), (0, 0, 0)) - 21.0).abs() < 1e-9);
+        assert_eq!(contrast_ratio((51, 102, 255), (51, 102, 255)), 1.0);
+        // #777 on white is just below AA, #767676 just above
+        assert!(contrast_ratio((119, 119, 119), (255, 255, 255)) < 4.5);
+        assert!(contrast_ratio((118, 118, 118), (255, 255, 255)) > 4.5);
+        assert!(is_readable((118, 118, 118), Background::Light, 4.5));
+    }
+
+    #[test]
+    fn ensure_contrast_reaches_the_ratio() {
+        for background in [Background::Dark, Background::Light] {
+            for rgb in SAMPLES {
+                let adapted = ensure_contrast(rgb, background, 4.5);
+                assert!(is_readable(adapted, background, 4.5), "{:?}", adapted);
+            }
+        }
+        assert_eq!(
+            ensure_contrast((255, 255, 85), Background::Dark, 4.5),
+            (255, 255, 85)
+        );
+    }
+
+    #[test]
+    fn palette_colors_are_readable() {
+        let colors = palette(20, Background::Light);
+        assert_eq!(colors.len(), 20);
+        assert!(colors
+            .iter()
+            .all(|c| is_readable(*c, Background::Light, 3.0)));
+        assert_ne!(colors[0], colors[1]);
+    }
+
+    #[test]
+    fn osc_replies_are_parsed() {
+        assert_eq!(
+            parse_osc_color("\x1b]11;rgb:ffff/8080/0000\x07"),
+            Some((255, 128, 0))
+        );
+        assert_eq!(
+            parse_osc_color("\x1b]11;rgb:f/8/0\x1b\\"),
+            Some((255, 136, 0))
+        );
+        assert_eq!(parse_osc_color("rgb:12/34"), None);
+        assert_eq!(parse_osc_color("garbage"), None);
+    }
+
+    #[test]
+    fn xterm_colors_map_to_themselves() {
+        for index in 16..256 {
+            let (r, g, b) = index2rgb(index);
+            assert_eq!(rgb2index(r, g, b), index, "{:?}", (r, g, b));
+        }
+        for index in 0..16 {
+            let (r, g, b) = index2rgb(index);
+            assert_eq!(rgb2ansi16(r, g, b), index, "{:?}", (r, g, b));
+        }
+        assert_eq!(index2rgb(110), (135, 175, 215));
+        assert_eq!(index2rgb(231), (255, 255, 255));
+        assert_eq!(index2rgb(255), (238, 238, 238));
+    }
+
+    #[test]
+    fn nearest_xterm_color() {
+        assert_eq!(rgb2index(135, 175, 215), 110);
+        assert_eq!(index2rgb(rgb2index(51, 102, 255)), (95, 95, 255));
+        assert_eq!(rgb2index(100, 100, 100), 241);
+    }
+
+    #[test]
+    fn gradients_hit_the_stops() {
+        let stops = [(0, 0, 0), (255, 255, 255)];
+        assert_eq!(
+            gradient(&stops, 3),
+            vec![(0, 0, 0), (128, 128, 128), (255, 255, 255)]
+        );
+        assert_eq!(gradient_at(&stops, 2.0), (255, 255, 255));
+        assert_eq!(rgb2index(255, 0, 0), 196);
+    }
+}
@@ -0,0 +1,545 @@
+use std::collections::HashMap;
+use std::time::Duration;
+#[cfg(feature = "reqwest")]
+use std::time::Instant;
+
+use colored::Colorize;
+use http::header::{HeaderMap, CONTENT_TYPE};
+use http::StatusCode;
+use serde_json::Value;
+use time::OffsetDateTime;
+
+use crate::printer::Printer;
+use crate::redact::redact_policy;
+use crate::request_ext::{
+    header_value_to_string, headers_to_hashmap, HttpMethod, RequestParts, ResponseParts,
+};
+use crate::termprint::{line, str_key_value, str_redacted_hashmap, str_title, ColoredItem, MEDIUM};
+
+const HEX_ROW: usize = 16;
+const INDENT: usize = 2;
+
+#[derive(Clone, Copy, Debug)]
+pub struct BodyLimits {
+    pub max_text: usize,
+    pub max_json: usize,
+    pub max_hex: usize,
+}
+
+impl Default for BodyLimits {
+    fn default() -> Self {
+        BodyLimits {
+            max_text: 4096,
+            max_json: 64 * 1024,
+            max_hex: 256,
+        }
+    }
+}
+
+// One request/response round trip. The response part stays empty until the
+// exchange has been completed with `with_response`.
+#[derive(Clone, Debug)]
+pub struct HttpExchange {
+    pub started: OffsetDateTime,
+    pub method: HttpMethod,
+    pub url: String,
+    pub request_headers: HeaderMap,
+    pub request_body: Option<Vec<u8>>,
+    pub version: Option<String>,
+    pub status: Option<StatusCode>,
+    pub response_headers: HeaderMap,
+    pub response_body: Option<Vec<u8>>,
+    pub elapsed: Option<Duration>,
+}
+
+impl HttpExchange {
+    pub fn from_request<R: RequestParts>(request: &R) -> Self {
+        HttpExchange {
+            started: OffsetDateTime::now_utc(),
+            method: request.http_method(),
+            url: request.http_url(),
+            request_headers: request.http_headers().clone(),
+            request_body: request.http_body().map(|b| b.to_vec()),
+            version: None,
+            status: None,
+            response_headers: HeaderMap::new(),
+            response_body: None,
+            elapsed: None,
+        }
+    }
+
+    pub fn with_response(
+        mut self,
+        status: StatusCode,
+        version: &str,
+        headers: &HeaderMap,
+        body: &[u8],
+    ) -> Self {
+        self.status = Some(status);
+        self.version = Some(version.to_string());
+        self.response_headers = headers.clone();
+        self.response_body = Some(body.to_vec());
+        self
+    }
+
+    pub fn with_response_parts<R: ResponseParts>(self, response: &R, body: &[u8]) -> Self {
+        self.with_response(
+            response.http_status(),
+            &format!("{:?}", response.http_version()),
+            response.http_headers(),
+            body,
+        )
+    }
+
+    pub fn with_elapsed(mut self, elapsed: Duration) -> Self {
+        self.elapsed = Some(elapsed);
+        self
+    }
+}
+
+// Sends the request and records both sides of the exchange including the
+// response body, which can be taken from `response_body` afterwards.
+#[cfg(feature = "reqwest")]
+pub async fn send_exchange(
+    client: &reqwest::Client,
+    request: reqwest::Request,
+) -> reqwest::Result<HttpExchange> {
+    let exchange = HttpExchange::from_request(&request);
+    let start = Instant::now();
+    let response = client.execute(request).await?;
+    let status = response.status();
+    let version = format!("{:?}", response.version());
+    let headers = response.headers().clone();
+    let body = response.bytes().await?;
+    Ok(exchange
+        .with_response(status, &version, &headers, &body)
+        .with_elapsed(start.elapsed()))
+}
+
+#[cfg(feature = "blocking")]
+pub fn send_exchange_blocking(
+    client: &reqwest::blocking::Client,
+    request: reqwest::blocking::Request,
+) -> reqwest::Result<HttpExchange> {
+    let exchange = HttpExchange::from_request(&request);
+    let start = Instant::now();
+    let response = client.execute(request)?;
+    let status = response.status();
+    let version = format!("{:?}", response.version());
+    let headers = response.headers().clone();
+    let body = response.bytes()?;
+    Ok(exchange
+        .with_response(status, &version, &headers, &body)
+        .with_elapsed(start.elapsed()))
+}
+
+pub fn str_status(status: StatusCode) -> String {
+    let status_str = status.to_string();
+    let status_str = status_str.as_str();
+    let colored = match status.as_u16() {
+        200..=299 => status_str.csuccess(),
+        300..=399 => status_str.cinfo(),
+        400..=499 => status_str.cwarning().bold(),
+        500..=599 => status_str.cerror(),
+        _ => status_str.cvar(),
+    };
+    colored.to_string()
+}
+
+pub fn str_exchange(exchange: &HttpExchange, limits: Option<BodyLimits>) -> String {
+    let limits = limits.unwrap_or_default();
+    let policy = redact_policy();
+    let mut output = String::new();
+
+    output.push_str(&str_title("Request"));
+    output.push_str(&line(MEDIUM));
+    output.push_str(&format!(
+        "{} {}\n",
+        exchange.method.as_str().ctitle(),
+        policy.redact_url(&exchange.url).as_str().cvar()
+    ));
+    output.push_str(&str_headers(&exchange.request_headers));
+    output.push_str(&str_body(
+        &exchange.request_headers,
+        exchange.request_body.as_deref(),
+        &limits,
+    ));
+
+    if let Some(status) = exchange.status {
+        output.push('\n');
+        output.push_str(&str_title("Response"));
+        output.push_str(&line(MEDIUM));
+        let version = exchange.version.clone().unwrap_or_default();
+        let elapsed = exchange
+            .elapsed
+            .map_or("".to_string(), |d| format!(" ({} ms)", d.as_millis()));
+        output.push_str(&format!(
+            "{} {}{}\n",
+            version.as_str().cinfo(),
+            str_status(status),
+            elapsed.as_str().cvar()
+        ));
+        output.push_str(&str_headers(&exchange.response_headers));
+        output.push_str(&str_body(
+            &exchange.response_headers,
+            exchange.response_body.as_deref(),
+            &limits,
+        ));
+    }
+    output
+}
+
+// Single line form: method, URL, status and timing.
+pub fn str_exchange_summary(exchange: &HttpExchange) -> String {
+    let url = redact_policy().redact_url(&exchange.url);
+    let status = exchange.status.map_or("-".to_string(), str_status);
+    let elapsed = exchange
+        .elapsed
+        .map_or("".to_string(), |d| format!(" ({} ms)", d.as_millis()));
+    format!(
+        "{} {} {}{}",
+        exchange.method.as_str().ctitle(),
+        url.as_str().cvar(),
+        status,
+        elapsed.as_str().cvar()
+    )
+}
+
+pub fn print_exchange(exchange: &HttpExchange, limits: Option<BodyLimits>) {
+    let _ = Printer::stdout().exchange(exchange, limits);
+}
+
+// Same layout as print_response, with the method taken from the recorded request.
+pub fn exchange_response_to_hashmap(exchange: &HttpExchange) -> HashMap<String, String> {
+    let mut map_resp: HashMap<String, String> = HashMap::new();
+    if let Some(status) = exchange.status {
+        map_resp.insert("Status".to_string(), status.to_string());
+    }
+    map_resp.insert("Method".to_string(), exchange.method.to_string());
+    map_resp.insert("URL".to_string(), redact_policy().redact_url(&exchange.url));
+    map_resp.extend(headers_to_hashmap(&exchange.response_headers));
+    map_resp
+}
+
+pub fn str_exchange_response(exchange: &HttpExchange) -> String {
+    str_redacted_hashmap(&exchange_response_to_hashmap(exchange), Some("Response"))
+}
+
+pub fn print_exchange_response(exchange: &HttpExchange) {
+    let _ = Printer::stdout().write_str(&str_exchange_response(exchange));
+}
+
+fn str_headers(headers: &HeaderMap) -> String {
+    if headers.is_empty() {
+        return String::new();
+    }
+    str_redacted_hashmap(&headers_to_hashmap(headers), None)
+}
+
+enum BodyKind {
+    Json,
+    Form,
+    Text,
+    Binary,
+}
+
+fn body_kind(headers: &HeaderMap, body: &[u8]) -> BodyKind {
+    let content_type = headers
+        .get(CONTENT_TYPE)
+        .map(|v| header_value_to_string(v).to_lowercase())
+        .unwrap_or_default();
+    let mime = content_type.split(';').next().unwrap_or("").trim();
+    if mime == "application/json" || mime.ends_with("+json") {
+        BodyKind::Json
+    } else if mime == "application/x-www-form-urlencoded" {
+        BodyKind::Form
+    } else if mime.starts_with("text/")
+        || mime.ends_with("xml")
+        || mime == "application/javascript"
+        || (mime.is_empty() && is_text(body))
+    {
+        BodyKind::Text
+    } else {
+        BodyKind::Binary
+    }
+}
+
+fn is_text(body: &[u8]) -> bool {
+    match std::str::from_utf8(body) {
+        Ok(s) => !s.chars().any(|c| c.is_control() && !c.is_whitespace()),
+        Err(_) => false,
+    }
+}
+
+pub fn str_body(headers: &HeaderMap, body: Option<&[u8]>, limits: &BodyLimits) -> String {
+    let Some(body) = body else {
+        return String::new();
+    };
+    if body.is_empty() {
+        return String::new();
+    }
+    let mut output = format!("{} ({} bytes)\n", "Body".cinfo(), body.len());
+    match body_kind(headers, body) {
+        BodyKind::Json if body.len() <= limits.max_json => {
+            match serde_json::from_slice::<Value>(body) {
+                Ok(mut json) => {
+                    redact_policy().redact_json(&mut json);
+                    output.push_str(&str_json(&json, 0));
+                    output.push('\n');
+                }
+                Err(_) => output.push_str(&str_text(body, limits.max_text)),
+            }
+        }
+        BodyKind::Json | BodyKind::Text => output.push_str(&str_text(body, limits.max_text)),
+        BodyKind::Form => output.push_str(&str_form(body)),
+        BodyKind::Binary => output.push_str(&str_hex_dump(body, limits.max_hex)),
+    }
+    output
+}
+
+fn str_truncated(len: usize, limit: usize) -> String {
+    if len > limit {
+        format!(
+            "{}\n",
+            format!("… {} more bytes", len - limit).as_str().cwarning()
+        )
+    } else {
+        String::new()
+    }
+}
+
+fn str_text(body: &[u8], max_len: usize) -> String {
+    let shown = &body[..body.len().min(max_len)];
+    let text = String::from_utf8_lossy(shown);
+    let mut output = format!("{}\n", text.trim_end().cvar());
+    output.push_str(&str_truncated(body.len(), max_len));
+    output
+}
+
+fn str_form(body: &[u8]) -> String {
+    let policy = redact_policy();
+    let pairs: Vec<(String, String)> = form_urlencoded::parse(body)
+        .map(|(k, v)| {
+            let value = policy.redact(&k, &v);
+            (k.into_owned(), value)
+        })
+        .collect();
+    let max_k = pairs.iter().map(|(k, _)| k.len()).max().unwrap_or(0);
+    pairs
+        .iter()
+        .map(|(k, v)| str_key_value(k, v, max_k, MEDIUM))
+        .collect()
+}
+
+pub fn str_hex_dump(body: &[u8], max_len: usize) -> String {
+    let shown = &body[..body.len().min(max_len)];
+    let mut output = String::new();
+    for (row, chunk) in shown.chunks(HEX_ROW).enumerate() {
+        let hex: Vec<String> = chunk.iter().map(|b| format!("{:02x}", b)).collect();
+        let ascii: String = chunk
+            .iter()
+            .map(|b| match b {
+                b' '..=b'~' => *b as char,
+                _ => '.',
+            })
+            .collect();
+        output.push_str(&format!(
+            "{}  {}  {}\n",
+            format!("{:08x}", row * HEX_ROW).as_str().cinfo(),
+            format!("{:<w$}", hex.join(" "), w = HEX_ROW * 3 - 1)
+                .as_str()
+                .cvar(),
+            format!("|{}|", ascii).as_str().citem()
+        ));
+    }
+    output.push_str(&str_truncated(body.len(), max_len));
+    output
+}
+
+pub fn str_json(value: &Value, indent: usize) -> String {
+    let pad = " ".repeat(indent + INDENT);
+    let end_pad = " ".repeat(indent);
+    match value {
+        Value::Object(map) if !map.is_empty() => {
+            let items: Vec<String> = map
+                .iter()
+                .map(|(k, v)| {
+                    format!(
+                        "{}{}: {}",
+                        pad,
+                        format!("\"{}\"", k).as_str().cinfo(),
+                        str_json(v, indent + INDENT)
+                    )
+                })
+                .collect();
+            format!("{{\n{}\n{}}}", items.join(",\n"), end_pad)
+        }
+        Value::Array(items) if !items.is_empty() => {
+            let items: Vec<String> = items
+                .iter()
+                .map(|v| format!("{}{}", pad, str_json(v, indent + INDENT)))
+                .collect();
+            format!("[\n{}\n{}]", items.join(",\n"), end_pad)
+        }
+        Value::String(_) => value.to_string().as_str().cvar().to_string(),
+        _ => value.to_string().as_str().citem().to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use http::HeaderValue;
+
+    use super::*;
+    use crate::printer::strip_ansi;
+    use crate::theme::Theme;
+
+    fn headers(content_type: &'static str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(CONTENT_TYPE, HeaderValue::from_static(content_type));
+        headers
+    }
+
+    fn body(content_type: &'static str, body: &[u8], limits: BodyLimits) -> String {
+        strip_ansi(&str_body(&headers(content_type), Some(body), &limits))
+    }
+
+    #[test]
+    fn json_bodies_are_pretty_printed() {
+        let output = body(
+            "application/json; charset=utf-8",
+            br#"{"id":1,"tags":["a"],"password":"hunter22"}"#,
+            BodyLimits::default(),
+        );
+        assert_eq!(
+            output,
+            "Body (43 bytes)\n{\n  \"id\": 1,\n  \"password\": \"********\",\n  \"tags\": [\n    \"a\"\n  ]\n}\n"
+        );
+    }
+
+    #[test]
+    fn large_or_invalid_json_is_shown_as_text() {
+        let limits = BodyLimits {
+            max_json: 4,
+            ..BodyLimits::default()
+        };
+        let output = body("application/problem+json", br#"{"a":1}"#, limits);
+        assert_eq!(output, "Body (7 bytes)\n{\"a\":1}\n");
+        let output = body("application/json", b"{not json", BodyLimits::default());
+        assert_eq!(output, "Body (9 bytes)\n{not json\n");
+    }
+
+    #[test]
+    fn form_bodies_are_listed() {
+        let output = body(
+            "application/x-www-form-urlencoded",
+            b"user=a+b&password=hunter22",
+            BodyLimits::default(),
+        );
+        assert!(
+            output.contains("user") && output.contains("a b"),
+            "{}",
+            output
+        );
+        assert!(output.contains("password"), "{}", output);
+        assert!(!output.contains("hunter22"), "{}", output);
+    }
+
+    #[test]
+    fn text_bodies_are_truncated() {
+        let limits = BodyLimits {
+            max_text: 5,
+            ..BodyLimits::default()
+        };
+        let output = body("text/plain", b"hello world", limits);
+        assert_eq!(output, "Body (11 bytes)\nhello\n… 6 more bytes\n");
+        // Without a content type, printable UTF-8 counts as text
+        let output = strip_ansi(&str_body(&HeaderMap::new(), Some(b"hi"), &limits));
+        assert_eq!(output, "Body (2 bytes)\nhi\n");
+    }
+
+    #[test]
+    fn binary_bodies_are_hex_dumped() {
+        let limits = BodyLimits {
+            max_hex: 18,
+            ..BodyLimits::default()
+        };
+        let bytes: Vec<u8> = (0x41..0x55).collect();
+        let output = body("image/png", &bytes, limits);
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines[0], "Body (20 bytes)");
+        assert_eq!(
+            lines[1],
+            "00000000  41 42 43 44 45 46 47 48 49 4a 4b 4c 4d 4e 4f 50  |ABCDEFGHIJKLMNOP|"
+        );
+        assert_eq!(lines[2], format!("00000010  51 52{}  |QR|", " ".repeat(42)));
+        assert_eq!(lines[3], "… 2 more bytes");
+
+        let output = strip_ansi(&str_body(&HeaderMap::new(), Some(&[0, 1, 0xff]), &limits));
+        assert!(
+            output.contains("00 01 ff") && output.contains("|...|"),
+            "{}",
+            output
+        );
+    }
+
+    #[test]
+    fn empty_bodies_print_nothing() {
+        let limits = BodyLimits::default();
+        assert_eq!(str_body(&HeaderMap::new(), None, &limits), "");
+        assert_eq!(str_body(&HeaderMap::new(), Some(b""), &limits), "");
+    }
+
+    #[test]
+    fn status_is_colored_by_class() {
+        colored::control::set_override(true);
+        let printer = Printer::new(Vec::new()).theme(Theme::basic());
+        type Paint = fn(&str) -> colored::ColoredString;
+        let cases: [(StatusCode, Paint); 4] = [
+            (StatusCode::OK, |s| s.csuccess()),
+            (StatusCode::FOUND, |s| s.cinfo()),
+            (StatusCode::NOT_FOUND, |s| s.cwarning().bold()),
+            (StatusCode::BAD_GATEWAY, |s| s.cerror()),
+        ];
+        let mut outputs = vec![];
+        for (status, paint) in cases {
+            let output = printer.render(|| str_status(status));
+            let expected = printer.render(|| paint(&status.to_string()).to_string());
+            assert_eq!(output, expected);
+            assert_eq!(strip_ansi(&output), status.to_string());
+            outputs.push(output);
+        }
+        outputs.dedup();
+        assert_eq!(outputs.len(), 4, "{:?}", outputs);
+    }
+
+    #[test]
+    fn response_hashmap() {
+        let mut response_headers = headers("application/json");
+        response_headers.insert("x-request-id", HeaderValue::from_static("abc"));
+        let exchange = HttpExchange {
+            started: OffsetDateTime::UNIX_EPOCH,
+            method: HttpMethod::PUT,
+            url: "https://example.com/a?token=secret123".to_string(),
+            request_headers: HeaderMap::new(),
+            request_body: None,
+            version: None,
+            status: None,
+            response_headers,
+            response_body: None,
+            elapsed: None,
+        };
+        let map = exchange_response_to_hashmap(&exchange);
+        assert!(!map.contains_key("Status"));
+        assert_eq!(map["Method"], "PUT");
+        assert!(!map["URL"].contains("secret123"), "{}", map["URL"]);
+        assert_eq!(map["x-request-id"], "abc");
+        assert_eq!(map["content-type"], "application/json");
+
+        let exchange =
+            exchange.with_response(StatusCode::CREATED, "HTTP/1.1", &HeaderMap::new(), b"");
+        let map = exchange_response_to_hashmap(&exchange);
+        assert_eq!(map["Status"], "201 Created");
+        assert_eq!(map.len(), 3);
+    }
+}
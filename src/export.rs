@@ -0,0 +1,486 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::sync::Mutex;
+
+use base64::Engine;
+use http::header::{HeaderMap, CONTENT_TYPE, LOCATION};
+use serde_json::{json, Value};
+use time::format_description::well_known::Rfc3339;
+
+use crate::exchange::HttpExchange;
+use crate::printer::Printer;
+use crate::redact::redact_policy;
+use crate::request_ext::{header_value_to_string, HttpMethod, RequestParts};
+
+pub const HAR_VERSION: &str = "1.2";
+
+#[derive(Clone, Copy, Debug)]
+pub struct CurlOptions {
+    pub redact: bool,
+    pub multiline: bool,
+}
+
+impl Default for CurlOptions {
+    fn default() -> Self {
+        CurlOptions {
+            redact: true,
+            multiline: true,
+        }
+    }
+}
+
+pub fn request_to_curl<R: RequestParts>(request: &R, options: Option<CurlOptions>) -> String {
+    make_curl(
+        &request.http_method(),
+        &request.http_url(),
+        request.http_headers(),
+        request.http_body(),
+        options.unwrap_or_default(),
+    )
+}
+
+pub fn exchange_to_curl(exchange: &HttpExchange, options: Option<CurlOptions>) -> String {
+    make_curl(
+        &exchange.method,
+        &exchange.url,
+        &exchange.request_headers,
+        exchange.request_body.as_deref(),
+        options.unwrap_or_default(),
+    )
+}
+
+pub fn print_curl<R: RequestParts>(request: &R) {
+    let _ = Printer::stdout().curl(request);
+}
+
+fn make_curl(
+    method: &HttpMethod,
+    url: &str,
+    headers: &HeaderMap,
+    body: Option<&[u8]>,
+    options: CurlOptions,
+) -> String {
+    let policy = redact_policy();
+    let mut args: Vec<String> = vec!["curl".to_string()];
+    let has_body = body.is_some_and(|b| !b.is_empty());
+    match method {
+        HttpMethod::GET if !has_body => {}
+        HttpMethod::POST if has_body => {}
+        HttpMethod::HEAD => args.push("--head".to_string()),
+        _ => args.push(format!("-X {}", shell_quote(method.as_str()))),
+    }
+    let url = if options.redact {
+        policy.redact_url(url)
+    } else {
+        url.to_string()
+    };
+    args.push(shell_quote(&url));
+    for (key, value) in headers {
+        let value = header_value_to_string(value);
+        let value = if options.redact {
+            policy.redact(key.as_str(), &value)
+        } else {
+            value
+        };
+        args.push(format!(
+            "-H {}",
+            shell_quote(&format!("{}: {}", key, value))
+        ));
+    }
+    if let Some(body) = body.filter(|b| !b.is_empty()) {
+        match std::str::from_utf8(body) {
+            Ok(_) if options.redact => args.push(format!(
+                "--data-raw {}",
+                shell_quote(&redact_body(headers, body))
+            )),
+            Ok(text) => args.push(format!("--data-raw {}", shell_quote(text))),
+            Err(_) => args.push(format!("--data-binary {}", ansi_c_quote(body))),
+        }
+    }
+    let separator = if options.multiline { " \\\n  " } else { " " };
+    args.join(separator)
+}
+
+// POSIX single quoting: everything is literal except the quote itself.
+pub fn shell_quote(s: &str) -> String {
+    if !s.is_empty()
+        && s.chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_./:@%+=,".contains(c))
+    {
+        return s.to_string();
+    }
+    format!("'{}'", s.replace('\'', "'\\''"))
+}
+
+// Bash $'..' quoting for bodies that are not valid UTF-8.
+fn ansi_c_quote(bytes: &[u8]) -> String {
+    let escaped: String = bytes
+        .iter()
+        .map(|b| match b {
+            b'\'' | b'\\' => format!("\\{}", *b as char),
+            b' '..=b'~' => (*b as char).to_string(),
+            _ => format!("\\x{:02x}", b),
+        })
+        .collect();
+    format!("$'{}'", escaped)
+}
+
+// Collects request/response pairs and writes them as a HAR 1.2 log that can be
+// imported into browser dev tools.
+#[derive(Debug)]
+pub struct HarRecorder {
+    entries: Mutex<Vec<HttpExchange>>,
+    redact: bool,
+}
+
+impl Default for HarRecorder {
+    fn default() -> Self {
+        HarRecorder::new(true)
+    }
+}
+
+impl HarRecorder {
+    pub fn new(redact: bool) -> Self {
+        HarRecorder {
+            entries: Mutex::new(vec![]),
+            redact,
+        }
+    }
+
+    pub fn record(&self, exchange: &HttpExchange) {
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.push(exchange.clone());
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.lock().map(|e| e.len()).unwrap_or(0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn to_json(&self) -> Value {
+        let entries: Vec<Value> = self
+            .entries
+            .lock()
+            .map(|entries| entries.iter().map(|e| self.har_entry(e)).collect())
+            .unwrap_or_default();
+        json!({
+            "log": {
+                "version": HAR_VERSION,
+                "creator": {
+                    "name": env!("CARGO_PKG_NAME"),
+                    "version": env!("CARGO_PKG_VERSION"),
+                },
+                "entries": entries,
+            }
+        })
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let har = serde_json::to_string_pretty(&self.to_json())?;
+        fs::write(path, har)
+    }
+
+    fn har_entry(&self, exchange: &HttpExchange) -> Value {
+        let policy = redact_policy();
+        let url = if self.redact {
+            policy.redact_url(&exchange.url)
+        } else {
+            exchange.url.clone()
+        };
+        let http_version = exchange.version.clone().unwrap_or("HTTP/1.1".to_string());
+        let time_ms = exchange.elapsed.map_or(0.0, |d| d.as_secs_f64() * 1000.0);
+
+        let mut request = json!({
+            "method": exchange.method.as_str(),
+            "url": url,
+            "httpVersion": http_version,
+            "cookies": [],
+            "headers": self.har_headers(&exchange.request_headers),
+            "queryString": har_query(&url),
+            "headersSize": -1,
+            "bodySize": exchange.request_body.as_ref().map_or(0, |b| b.len()),
+        });
+        if let Some(body) = exchange.request_body.as_ref().filter(|b| !b.is_empty()) {
+            request["postData"] = json!({
+                "mimeType": content_type(&exchange.request_headers),
+                "text": self.har_text(&exchange.request_headers, body),
+            });
+        }
+
+        let response = match exchange.status {
+            Some(status) => {
+                let body = exchange.response_body.clone().unwrap_or_default();
+                let mut content = json!({
+                    "size": body.len(),
+                    "mimeType": content_type(&exchange.response_headers),
+                });
+                match std::str::from_utf8(&body) {
+                    Ok(_) => {
+                        content["text"] = json!(self.har_text(&exchange.response_headers, &body))
+                    }
+                    Err(_) => {
+                        content["text"] =
+                            json!(base64::engine::general_purpose::STANDARD.encode(&body));
+                        content["encoding"] = json!("base64");
+                    }
+                }
+                json!({
+                    "status": status.as_u16(),
+                    "statusText": status.canonical_reason().unwrap_or(""),
+                    "httpVersion": http_version,
+                    "cookies": [],
+                    "headers": self.har_headers(&exchange.response_headers),
+                    "content": content,
+                    "redirectURL": exchange
+                        .response_headers
+                        .get(LOCATION)
+                        .map(header_value_to_string)
+                        .unwrap_or_default(),
+                    "headersSize": -1,
+                    "bodySize": body.len(),
+                })
+            }
+            // No response was received, HAR uses status 0 for aborted requests
+            None => json!({
+                "status": 0,
+                "statusText": "",
+                "httpVersion": http_version,
+                "cookies": [],
+                "headers": [],
+                "content": { "size": 0, "mimeType": "" },
+                "redirectURL": "",
+                "headersSize": -1,
+                "bodySize": -1,
+            }),
+        };
+
+        json!({
+            "startedDateTime": exchange.started.format(&Rfc3339).unwrap_or_default(),
+            "time": time_ms,
+            "request": request,
+            "response": response,
+            "cache": {},
+            "timings": { "send": 0, "wait": time_ms, "receive": 0 },
+        })
+    }
+
+    fn har_text(&self, headers: &HeaderMap, body: &[u8]) -> String {
+        if self.redact {
+            redact_body(headers, body)
+        } else {
+            String::from_utf8_lossy(body).to_string()
+        }
+    }
+
+    fn har_headers(&self, headers: &HeaderMap) -> Vec<Value> {
+        let policy = redact_policy();
+        headers
+            .iter()
+            .map(|(key, value)| {
+                let value = header_value_to_string(value);
+                let value = if self.redact {
+                    policy.redact(key.as_str(), &value)
+                } else {
+                    value
+                };
+                json!({ "name": key.as_str(), "value": value })
+            })
+            .collect()
+    }
+}
+
+// JSON and form bodies get the same redaction as the headers, other bodies
+// are kept as they are.
+fn redact_body(headers: &HeaderMap, body: &[u8]) -> String {
+    let text = String::from_utf8_lossy(body).to_string();
+    let policy = redact_policy();
+    let mime = content_type(headers).to_lowercase();
+    if mime.contains("json") {
+        match serde_json::from_slice::<Value>(body) {
+            Ok(mut json) => {
+                policy.redact_json(&mut json);
+                json.to_string()
+            }
+            Err(_) => text,
+        }
+    } else if mime.starts_with("application/x-www-form-urlencoded") {
+        form_urlencoded::Serializer::new(String::new())
+            .extend_pairs(
+                form_urlencoded::parse(body).map(|(k, v)| (k.clone(), policy.redact(&k, &v))),
+            )
+            .finish()
+    } else {
+        text
+    }
+}
+
+fn har_query(url: &str) -> Vec<Value> {
+    let query = url
+        .split_once('?')
+        .map(|(_, q)| q.split('#').next().unwrap_or(""))
+        .unwrap_or("");
+    form_urlencoded::parse(query.as_bytes())
+        .map(|(k, v)| json!({ "name": k, "value": v }))
+        .collect()
+}
+
+fn content_type(headers: &HeaderMap) -> String {
+    headers
+        .get(CONTENT_TYPE)
+        .map(header_value_to_string)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use http::{HeaderValue, StatusCode};
+    use time::OffsetDateTime;
+
+    fn exchange() -> HttpExchange {
+        let mut request_headers = HeaderMap::new();
+        request_headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+        request_headers.insert(
+            "authorization",
+            HeaderValue::from_static("Bearer sk-0123456789abcdef"),
+        );
+        let mut response_headers = HeaderMap::new();
+        response_headers.insert(CONTENT_TYPE, HeaderValue::from_static("image/png"));
+        HttpExchange {
+            started: OffsetDateTime::UNIX_EPOCH,
+            method: HttpMethod::POST,
+            url: "https://example.com/items?page=2&token=secret123".to_string(),
+            request_headers,
+            request_body: Some(br#"{"name":"widget","password":"hunter22"}"#.to_vec()),
+            version: Some("HTTP/1.1".to_string()),
+            status: Some(StatusCode::CREATED),
+            response_headers,
+            response_body: Some(vec![0x89, b'P', b'N', b'G', 0xff]),
+            elapsed: Some(std::time::Duration::from_millis(250)),
+        }
+    }
+
+    #[test]
+    fn shell_quote_leaves_safe_words() {
+        assert_eq!(
+            shell_quote("https://example.com/a?b"),
+            "'https://example.com/a?b'"
+        );
+        assert_eq!(
+            shell_quote("https://example.com/a"),
+            "https://example.com/a"
+        );
+        assert_eq!(shell_quote("a=b,c+d@e%f"), "a=b,c+d@e%f");
+    }
+
+    #[test]
+    fn shell_quote_escapes_single_quotes() {
+        assert_eq!(shell_quote(""), "''");
+        assert_eq!(shell_quote("hello world"), "'hello world'");
+        assert_eq!(shell_quote("it's"), "'it'\\''s'");
+        assert_eq!(shell_quote("$HOME `id`"), "'$HOME `id`'");
+    }
+
+    #[test]
+    fn ansi_c_quote_escapes_bytes() {
+        assert_eq!(ansi_c_quote(b"plain text"), "$'plain text'");
+        assert_eq!(ansi_c_quote(b"it's a \\"), "$'it\\'s a \\\\'");
+        assert_eq!(
+            ansi_c_quote(&[0x00, b'\n', 0x7f, 0xff]),
+            "$'\\x00\\x0a\\x7f\\xff'"
+        );
+    }
+
+    #[test]
+    fn har_log_has_entries() {
+        let recorder = HarRecorder::new(true);
+        assert!(recorder.is_empty());
+        recorder.record(&exchange());
+        assert_eq!(recorder.len(), 1);
+
+        let har = recorder.to_json();
+        assert_eq!(har["log"]["version"], HAR_VERSION);
+        let entry = &har["log"]["entries"][0];
+        assert_eq!(entry["startedDateTime"], "1970-01-01T00:00:00Z");
+        assert_eq!(entry["time"], 250.0);
+
+        let request = &entry["request"];
+        assert_eq!(request["method"], "POST");
+        assert_eq!(
+            request["queryString"][0],
+            json!({ "name": "page", "value": "2" })
+        );
+        assert_eq!(request["postData"]["mimeType"], "application/json");
+
+        let response = &entry["response"];
+        assert_eq!(response["status"], 201);
+        assert_eq!(response["statusText"], "Created");
+        assert_eq!(response["content"]["encoding"], "base64");
+        assert_eq!(response["content"]["text"], "iVBOR/8=");
+        assert_eq!(response["bodySize"], 5);
+    }
+
+    #[test]
+    fn har_redacts_secrets() {
+        let recorder = HarRecorder::new(true);
+        recorder.record(&exchange());
+        let har = recorder.to_json().to_string();
+        assert!(har.contains("widget"), "{}", har);
+        for secret in ["sk-0123456789abcdef", "secret123", "hunter22"] {
+            assert!(!har.contains(secret), "{} in {}", secret, har);
+        }
+
+        let recorder = HarRecorder::new(false);
+        recorder.record(&exchange());
+        let har = recorder.to_json().to_string();
+        assert!(har.contains("sk-0123456789abcdef"), "{}", har);
+        assert!(har.contains("hunter22"), "{}", har);
+    }
+
+    #[test]
+    fn curl_redacts_secrets_in_bodies() {
+        let curl = exchange_to_curl(&exchange(), None);
+        assert!(curl.contains("widget"), "{}", curl);
+        assert!(!curl.contains("hunter22"), "{}", curl);
+        assert!(!curl.contains("sk-0123456789abcdef"), "{}", curl);
+
+        let mut form = exchange();
+        form.request_headers.insert(
+            CONTENT_TYPE,
+            HeaderValue::from_static("application/x-www-form-urlencoded"),
+        );
+        form.request_body = Some(b"user=a&password=hunter22".to_vec());
+        let curl = exchange_to_curl(&form, None);
+        assert!(curl.contains("user=a"), "{}", curl);
+        assert!(!curl.contains("hunter22"), "{}", curl);
+
+        let options = CurlOptions {
+            redact: false,
+            multiline: false,
+        };
+        let curl = exchange_to_curl(&form, Some(options));
+        assert!(
+            curl.contains("--data-raw 'user=a&password=hunter22'"),
+            "{}",
+            curl
+        );
+    }
+
+    #[test]
+    fn har_without_response_is_aborted() {
+        let recorder = HarRecorder::default();
+        recorder.record(&HttpExchange {
+            status: None,
+            response_body: None,
+            ..exchange()
+        });
+        let response = &recorder.to_json()["log"]["entries"][0]["response"];
+        assert_eq!(response["status"], 0);
+        assert_eq!(response["bodySize"], -1);
+    }
+}
@@ -0,0 +1,95 @@
+// Charts drawn with text cells. They scale to get_terminal_width() (or the
+// width of the rendering Printer) and color series with the theme's column colors.
+use std::fmt::Write;
+
+use crate::printer::Printer;
+use crate::termprint::ColoredItem;
+
+mod bar;
+mod braille;
+mod heatmap;
+mod histogram;
+mod sparkline;
+
+pub use bar::{print_bar_chart, str_bar_chart, BarChart, Orientation};
+pub use braille::{BrailleCanvas, Plot, Scale};
+pub use heatmap::{Heatmap, VIRIDIS};
+pub use histogram::{print_histogram, quantile, str_histogram, str_summary, Binning, Histogram};
+pub use sparkline::{str_sparkline, str_sparkline_opt, str_sparkline_range};
+
+// Left-aligned eighth blocks, index = number of eighths filled
+pub(crate) const HBLOCKS: [&str; 9] = [" ", "▏", "▎", "▍", "▌", "▋", "▊", "▉", "█"];
+// Bottom-aligned eighth blocks
+pub(crate) const VBLOCKS: [&str; 9] = [" ", "▁", "▂", "▃", "▄", "▅", "▆", "▇", "█"];
+
+pub trait Chart {
+    fn render(&self) -> String;
+
+    fn print(&self)
+    where
+        Self: Sized,
+    {
+        let _ = Printer::stdout().chart(self);
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Series {
+    pub name: String,
+    pub values: Vec<f64>,
+}
+
+impl Series {
+    pub fn new(name: &str, values: &[f64]) -> Self {
+        Series {
+            name: name.to_string(),
+            values: values.to_vec(),
+        }
+    }
+}
+
+// Compact number for labels and ticks: integers without decimals, tiny
+// values in exponent notation, others with at most two decimals.
+pub(crate) fn str_value(value: f64) -> String {
+    if value.fract() == 0.0 && value.abs() < 1e15 {
+        format!("{:.0}", value)
+    } else if value.abs() < 0.01 {
+        format!("{:.1e}", value)
+    } else {
+        let s = format!("{:.2}", value);
+        s.trim_end_matches('0').trim_end_matches('.').to_string()
+    }
+}
+
+// Left-aligned horizontal bar of `cells` width for value/max, with eighth-cell precision.
+pub(crate) fn hbar(value: f64, max: f64, cells: usize) -> String {
+    let eighths = (value / max * (cells * 8) as f64)
+        .round()
+        .clamp(0.0, (cells * 8) as f64) as usize;
+    let mut bar = HBLOCKS[8].repeat(eighths / 8);
+    if !eighths.is_multiple_of(8) {
+        bar.push_str(HBLOCKS[eighths % 8]);
+    }
+    bar
+}
+
+pub(crate) fn str_legend(names: &[&str], indent: usize) -> String {
+    let mut output = " ".repeat(indent);
+    for (i, name) in names.iter().enumerate() {
+        let _ = write!(output, "{} {}  ", "■".column(i), name.cvar());
+    }
+    format!("{}\n", output.trim_end())
+}
+
+// Pads or truncates to exactly `width` characters, centered.
+pub(crate) fn center(s: &str, width: usize) -> String {
+    let s: String = s.chars().take(width).collect();
+    let len = s.chars().count();
+    let left = (width - len) / 2;
+    format!(
+        "{}{}{}",
+        " ".repeat(left),
+        s,
+        " ".repeat(width - len - left)
+    )
+}
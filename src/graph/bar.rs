@@ -0,0 +1,369 @@
+use colored::ColoredString;
+
+use crate::graph::{center, hbar, str_legend, str_value, Chart, Series, VBLOCKS};
+use crate::termprint::{get_terminal_width, str_title, ColoredItem};
+
+const GROUP_GAP: usize = 2;
+const DEFAULT_HEIGHT: usize = 10;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Orientation {
+    #[default]
+    Horizontal,
+    Vertical,
+}
+
+// Bar chart of one or more series over the same labels. Negative values
+// are drawn as empty bars.
+#[derive(Clone, Debug)]
+pub struct BarChart {
+    labels: Vec<String>,
+    series: Vec<Series>,
+    title: Option<String>,
+    orientation: Orientation,
+    width: Option<usize>,
+    height: usize,
+    max: Option<f64>,
+    show_values: bool,
+}
+
+impl BarChart {
+    pub fn new(labels: &[&str]) -> Self {
+        BarChart {
+            labels: labels.iter().map(|l| l.to_string()).collect(),
+            series: vec![],
+            title: None,
+            orientation: Orientation::Horizontal,
+            width: None,
+            height: DEFAULT_HEIGHT,
+            max: None,
+            show_values: true,
+        }
+    }
+
+    pub fn series(mut self, name: &str, values: &[f64]) -> Self {
+        self.series.push(Series::new(name, values));
+        self
+    }
+
+    pub fn title(mut self, title: &str) -> Self {
+        self.title = Some(title.to_string());
+        self
+    }
+
+    pub fn orientation(mut self, orientation: Orientation) -> Self {
+        self.orientation = orientation;
+        self
+    }
+
+    pub fn vertical(self) -> Self {
+        self.orientation(Orientation::Vertical)
+    }
+
+    // Total width in columns, defaults to the terminal width
+    pub fn width(mut self, width: usize) -> Self {
+        self.width = Some(width);
+        self
+    }
+
+    // Rows of a vertical chart
+    pub fn height(mut self, height: usize) -> Self {
+        self.height = height.max(1);
+        self
+    }
+
+    // Fixed top of the scale instead of the largest value
+    pub fn max(mut self, max: f64) -> Self {
+        self.max = Some(max);
+        self
+    }
+
+    pub fn values(mut self, show: bool) -> Self {
+        self.show_values = show;
+        self
+    }
+
+    // Value as given, missing values are 0
+    fn raw_value(&self, series: usize, index: usize) -> f64 {
+        self.series[series]
+            .values
+            .get(index)
+            .copied()
+            .unwrap_or(0.0)
+    }
+
+    // Length of the bar, negative values have none
+    fn value(&self, series: usize, index: usize) -> f64 {
+        self.raw_value(series, index).max(0.0)
+    }
+
+    fn scale_max(&self) -> f64 {
+        let max = self.max.unwrap_or_else(|| {
+            self.series
+                .iter()
+                .flat_map(|s| s.values.iter().copied())
+                .fold(0.0, f64::max)
+        });
+        if max > 0.0 {
+            max
+        } else {
+            1.0
+        }
+    }
+
+    fn legend(&self, indent: usize) -> String {
+        if self.series.len() < 2 {
+            return String::new();
+        }
+        let names: Vec<&str> = self.series.iter().map(|s| s.name.as_str()).collect();
+        str_legend(&names, indent)
+    }
+
+    fn render_horizontal(&self) -> String {
+        let width = self.width.unwrap_or_else(get_terminal_width);
+        let max = self.scale_max();
+        let label_w = self
+            .labels
+            .iter()
+            .map(|l| l.chars().count())
+            .max()
+            .unwrap_or(0);
+        let value_w = if self.show_values {
+            self.series
+                .iter()
+                .flat_map(|s| s.values.iter())
+                .map(|v| str_value(*v).len() + 1)
+                .max()
+                .unwrap_or(0)
+        } else {
+            0
+        };
+        let bar_w = width.saturating_sub(label_w + 2 + value_w).max(1);
+
+        let mut output = String::new();
+        for (i, label) in self.labels.iter().enumerate() {
+            for s in 0..self.series.len() {
+                let name = if s == 0 { label.as_str() } else { "" };
+                let value = self.value(s, i);
+                output.push_str(&format!(
+                    "{} {}{}",
+                    format!("{:<label_w$}", name).as_str().cinfo(),
+                    "│".cline(),
+                    hbar(value, max, bar_w).column(s)
+                ));
+                if self.show_values {
+                    output.push_str(&format!(" {}", str_value(self.raw_value(s, i)).cvar()));
+                }
+                output.push('\n');
+            }
+        }
+        output.push_str(&self.legend(label_w + 2));
+        output
+    }
+
+    fn render_vertical(&self) -> String {
+        let width = self.width.unwrap_or_else(get_terminal_width);
+        let max = self.scale_max();
+        let height = self.height;
+        let n = self.labels.len().max(1);
+        let s = self.series.len().max(1);
+        let tick_w = str_value(max).len();
+        let axis_w = tick_w + 2;
+
+        let value_w = self
+            .series
+            .iter()
+            .flat_map(|s| s.values.iter())
+            .map(|v| str_value(*v).len())
+            .max()
+            .unwrap_or(1);
+        let label_w = self
+            .labels
+            .iter()
+            .map(|l| l.chars().count())
+            .max()
+            .unwrap_or(0);
+        let mut bar_w = if self.show_values { value_w } else { 1 };
+        let mut group_w = (s * bar_w).max(label_w);
+        let available = width.saturating_sub(axis_w);
+        if n * (group_w + GROUP_GAP) > available {
+            group_w = (available / n).saturating_sub(GROUP_GAP).max(s);
+            bar_w = (group_w / s).max(1);
+            group_w = group_w.max(s * bar_w);
+        }
+        let show_values = self.show_values && bar_w >= value_w;
+        let pad_left = (group_w - s * bar_w) / 2;
+
+        let mut output = String::new();
+        let rows = height + usize::from(show_values);
+        for b in (0..rows).rev() {
+            let axis = if b == height - 1 {
+                format!("{:>tick_w$} {}", str_value(max), "┤".cline())
+            } else if b < height {
+                format!("{:>tick_w$} {}", "", "│".cline())
+            } else {
+                " ".repeat(axis_w)
+            };
+            let mut line = axis;
+            for i in 0..self.labels.len() {
+                line.push_str(&" ".repeat(GROUP_GAP + pad_left));
+                for j in 0..self.series.len() {
+                    let value = self.value(j, i);
+                    let eighths = (value / max * (height * 8) as f64)
+                        .round()
+                        .min((height * 8) as f64) as usize;
+                    let (full, rem) = (eighths / 8, eighths % 8);
+                    let top = full + usize::from(rem > 0);
+                    let cell = if b < full {
+                        VBLOCKS[8].repeat(bar_w).column(j).to_string()
+                    } else if b == full && rem > 0 {
+                        VBLOCKS[rem].repeat(bar_w).column(j).to_string()
+                    } else if show_values && b == top {
+                        paint_centered(&str_value(self.raw_value(j, i)), bar_w, |v| v.cvar())
+                    } else {
+                        " ".repeat(bar_w)
+                    };
+                    line.push_str(&cell);
+                }
+                line.push_str(&" ".repeat(group_w - pad_left - s * bar_w));
+            }
+            output.push_str(line.trim_end());
+            output.push('\n');
+        }
+        output.push_str(&format!(
+            "{:>tick_w$} {}\n",
+            "0",
+            format!("└{}", "─".repeat(self.labels.len() * (group_w + GROUP_GAP))).cline()
+        ));
+        let mut labels = " ".repeat(axis_w);
+        for label in &self.labels {
+            labels.push_str(&" ".repeat(GROUP_GAP));
+            labels.push_str(&paint_centered(label, group_w, |l| l.cinfo()));
+        }
+        output.push_str(labels.trim_end());
+        output.push('\n');
+        output.push_str(&self.legend(axis_w + GROUP_GAP));
+        output
+    }
+}
+
+impl Chart for BarChart {
+    fn render(&self) -> String {
+        let mut output = self.title.as_deref().map(str_title).unwrap_or_default();
+        output.push_str(&match self.orientation {
+            Orientation::Horizontal => self.render_horizontal(),
+            Orientation::Vertical => self.render_vertical(),
+        });
+        output
+    }
+}
+
+// Colors only the text, so trailing padding can be trimmed off the line.
+fn paint_centered(s: &str, width: usize, paint: fn(&str) -> ColoredString) -> String {
+    let centered = center(s, width);
+    let text = centered.trim();
+    let left = centered.len() - centered.trim_start().len();
+    let right = centered.len() - left - text.len();
+    format!("{}{}{}", " ".repeat(left), paint(text), " ".repeat(right))
+}
+
+fn simple_chart(labels: &[&str], values: &[f64], title: Option<&str>) -> BarChart {
+    let chart = BarChart::new(labels).series("", values);
+    match title {
+        Some(t) => chart.title(t),
+        None => chart,
+    }
+}
+
+pub fn str_bar_chart(labels: &[&str], values: &[f64], title: Option<&str>) -> String {
+    simple_chart(labels, values, title).render()
+}
+
+pub fn print_bar_chart(labels: &[&str], values: &[f64], title: Option<&str>) {
+    simple_chart(labels, values, title).print();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::printer::strip_ansi;
+
+    fn render(chart: BarChart) -> String {
+        strip_ansi(&chart.render())
+    }
+
+    #[test]
+    fn bars_scale_to_the_width() {
+        let chart = BarChart::new(&["a", "bb"])
+            .series("", &[10.0, 5.0])
+            .width(20);
+        assert_eq!(
+            render(chart),
+            format!("a  │{} 10\nbb │{} 5\n", "█".repeat(13), "█".repeat(6) + "▌")
+        );
+        let chart = BarChart::new(&["a"])
+            .series("", &[3.0])
+            .max(12.0)
+            .values(false)
+            .width(11);
+        assert_eq!(render(chart), "a │██\n");
+    }
+
+    #[test]
+    fn empty_and_zero_data() {
+        assert_eq!(render(BarChart::new(&[]).series("", &[]).width(20)), "");
+        let output = render(BarChart::new(&[]).vertical().height(2).width(20));
+        assert_eq!(output, "\n1 ┤\n  │\n0 └\n\n");
+
+        let chart = BarChart::new(&["a", "b"]).series("", &[0.0, 0.0]).width(10);
+        assert_eq!(render(chart), "a │ 0\nb │ 0\n");
+        // Missing values count as zero
+        let chart = BarChart::new(&["a", "b"])
+            .series("", &[2.0])
+            .values(false)
+            .width(6);
+        assert_eq!(render(chart), "a │███\nb │\n");
+    }
+
+    #[test]
+    fn negative_values_are_empty_bars() {
+        let chart = BarChart::new(&["up", "down"])
+            .series("", &[4.0, -3.0])
+            .width(14);
+        let output = render(chart);
+        assert_eq!(output, "up   │█████ 4\ndown │ -3\n");
+
+        let chart = BarChart::new(&["up", "down"])
+            .series("", &[4.0, -3.0])
+            .vertical()
+            .height(1)
+            .width(20);
+        assert_eq!(
+            render(chart),
+            "      4\n4 ┤   ██    -3\n0 └────────────\n      up   down\n"
+        );
+    }
+
+    #[test]
+    fn vertical_bars() {
+        let chart = BarChart::new(&["a", "b"])
+            .series("", &[4.0, 1.0])
+            .vertical()
+            .height(2)
+            .width(20);
+        assert_eq!(
+            render(chart),
+            "     4\n4 ┤  █  1\n  │  █  ▄\n0 └──────\n     a  b\n"
+        );
+    }
+
+    #[test]
+    fn several_series_get_a_legend() {
+        let chart = BarChart::new(&["a"])
+            .series("x", &[2.0])
+            .series("y", &[1.0])
+            .values(false)
+            .width(7);
+        assert_eq!(render(chart), "a │████\n  │██\n   ■ x  ■ y\n");
+    }
+}
@@ -0,0 +1,407 @@
+use crate::graph::{center, str_legend, str_value, Chart};
+use crate::termprint::{get_terminal_width, str_title, ColoredItem};
+
+const DEFAULT_HEIGHT: usize = 15;
+const BRAILLE_BASE: u32 = 0x2800;
+// Bit of the dot at (x, y) within a 2x4 cell
+const DOT_BITS: [[u8; 4]; 2] = [[0x01, 0x02, 0x04, 0x40], [0x08, 0x10, 0x20, 0x80]];
+
+// Grid of Braille cells with 2x4 dots each. Dot (0, 0) is the top left;
+// every cell keeps the color index of the last series drawn into it.
+#[derive(Clone, Debug)]
+pub struct BrailleCanvas {
+    cols: usize,
+    rows: usize,
+    cells: Vec<u8>,
+    colors: Vec<Option<usize>>,
+}
+
+impl BrailleCanvas {
+    // At least one column, so rows can always be rendered
+    pub fn new(cols: usize, rows: usize) -> Self {
+        let cols = cols.max(1);
+        BrailleCanvas {
+            cols,
+            rows,
+            cells: vec![0; cols * rows],
+            colors: vec![None; cols * rows],
+        }
+    }
+
+    pub fn dot_width(&self) -> usize {
+        self.cols * 2
+    }
+
+    pub fn dot_height(&self) -> usize {
+        self.rows * 4
+    }
+
+    // Dots outside the canvas are ignored.
+    pub fn set(&mut self, x: i64, y: i64, color: usize) {
+        if x < 0 || y < 0 || x >= self.dot_width() as i64 || y >= self.dot_height() as i64 {
+            return;
+        }
+        let (x, y) = (x as usize, y as usize);
+        let idx = (y / 4) * self.cols + x / 2;
+        self.cells[idx] |= DOT_BITS[x % 2][y % 4];
+        self.colors[idx] = Some(color);
+    }
+
+    pub fn line(&mut self, from: (i64, i64), to: (i64, i64), color: usize) {
+        let (mut x, mut y) = from;
+        let dx = (to.0 - x).abs();
+        let dy = -(to.1 - y).abs();
+        let sx = if x < to.0 { 1 } else { -1 };
+        let sy = if y < to.1 { 1 } else { -1 };
+        let mut err = dx + dy;
+        loop {
+            self.set(x, y, color);
+            if (x, y) == to {
+                break;
+            }
+            let e2 = 2 * err;
+            if e2 >= dy {
+                err += dy;
+                x += sx;
+            }
+            if e2 <= dx {
+                err += dx;
+                y += sy;
+            }
+        }
+    }
+
+    pub fn render_rows(&self) -> Vec<String> {
+        self.cells
+            .chunks(self.cols)
+            .zip(self.colors.chunks(self.cols))
+            .map(|(cells, colors)| {
+                cells
+                    .iter()
+                    .zip(colors)
+                    .map(|(&bits, color)| match color {
+                        Some(c) if bits > 0 => char::from_u32(BRAILLE_BASE + bits as u32)
+                            .unwrap_or(' ')
+                            .to_string()
+                            .column(*c)
+                            .to_string(),
+                        _ => " ".to_string(),
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Scale {
+    #[default]
+    Linear,
+    Log,
+}
+
+impl Scale {
+    fn apply(&self, v: f64) -> Option<f64> {
+        match self {
+            Scale::Linear => Some(v),
+            Scale::Log if v > 0.0 => Some(v.log10()),
+            Scale::Log => None,
+        }
+    }
+
+    fn invert(&self, v: f64) -> f64 {
+        match self {
+            Scale::Linear => v,
+            Scale::Log => 10f64.powf(v),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Kind {
+    Line,
+    Scatter,
+}
+
+#[derive(Clone, Debug)]
+struct PlotSeries {
+    name: String,
+    points: Vec<(f64, f64)>,
+    kind: Kind,
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+struct Axis {
+    range: Option<(f64, f64)>,
+    scale: Scale,
+}
+
+impl Axis {
+    // Range in scaled units, from the fixed range or the data.
+    fn bounds(&self, values: impl Iterator<Item = f64>) -> (f64, f64) {
+        let (min, max) = match self.range {
+            Some((min, max)) => (
+                self.scale.apply(min).unwrap_or(0.0),
+                self.scale.apply(max).unwrap_or(1.0),
+            ),
+            None => values
+                .filter_map(|v| self.scale.apply(v))
+                .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), v| {
+                    (lo.min(v), hi.max(v))
+                }),
+        };
+        if !min.is_finite() || !max.is_finite() {
+            (0.0, 1.0)
+        } else if min == max {
+            (min - 0.5, max + 0.5)
+        } else {
+            (min, max)
+        }
+    }
+
+    // Label of a scaled position, snapping rounding noise around zero.
+    fn tick(&self, v: f64, span: f64) -> String {
+        let v = if self.scale == Scale::Linear && v.abs() < span * 1e-3 {
+            0.0
+        } else {
+            v
+        };
+        str_value(self.scale.invert(v))
+    }
+}
+
+// XY plot of line and scatter series on a Braille canvas, with tick labels
+// on both axes and a legend.
+#[derive(Clone, Debug, Default)]
+pub struct Plot {
+    series: Vec<PlotSeries>,
+    title: Option<String>,
+    width: Option<usize>,
+    height: Option<usize>,
+    x: Axis,
+    y: Axis,
+    x_label: Option<String>,
+    y_label: Option<String>,
+}
+
+impl Plot {
+    pub fn new() -> Self {
+        Plot::default()
+    }
+
+    pub fn line(mut self, name: &str, points: &[(f64, f64)]) -> Self {
+        self.series.push(PlotSeries {
+            name: name.to_string(),
+            points: points.to_vec(),
+            kind: Kind::Line,
+        });
+        self
+    }
+
+    pub fn scatter(mut self, name: &str, points: &[(f64, f64)]) -> Self {
+        self.series.push(PlotSeries {
+            name: name.to_string(),
+            points: points.to_vec(),
+            kind: Kind::Scatter,
+        });
+        self
+    }
+
+    pub fn title(mut self, title: &str) -> Self {
+        self.title = Some(title.to_string());
+        self
+    }
+
+    pub fn width(mut self, width: usize) -> Self {
+        self.width = Some(width);
+        self
+    }
+
+    // Rows of the plot area
+    pub fn height(mut self, height: usize) -> Self {
+        self.height = Some(height.max(2));
+        self
+    }
+
+    pub fn x_range(mut self, min: f64, max: f64) -> Self {
+        self.x.range = Some((min, max));
+        self
+    }
+
+    pub fn y_range(mut self, min: f64, max: f64) -> Self {
+        self.y.range = Some((min, max));
+        self
+    }
+
+    // Log scales drop points that are not positive
+    pub fn x_log(mut self) -> Self {
+        self.x.scale = Scale::Log;
+        self
+    }
+
+    pub fn y_log(mut self) -> Self {
+        self.y.scale = Scale::Log;
+        self
+    }
+
+    pub fn x_label(mut self, label: &str) -> Self {
+        self.x_label = Some(label.to_string());
+        self
+    }
+
+    pub fn y_label(mut self, label: &str) -> Self {
+        self.y_label = Some(label.to_string());
+        self
+    }
+
+    fn points(&self) -> impl Iterator<Item = &(f64, f64)> {
+        self.series.iter().flat_map(|s| s.points.iter())
+    }
+}
+
+impl Chart for Plot {
+    fn render(&self) -> String {
+        let width = self.width.unwrap_or_else(get_terminal_width);
+        let rows = self.height.unwrap_or(DEFAULT_HEIGHT);
+        let (x_min, x_max) = self.x.bounds(self.points().map(|p| p.0));
+        let (y_min, y_max) = self.y.bounds(self.points().map(|p| p.1));
+
+        let y_ticks = [y_max, (y_min + y_max) / 2.0, y_min].map(|v| self.y.tick(v, y_max - y_min));
+        let tick_w = y_ticks.iter().map(|t| t.len()).max().unwrap_or(1);
+        let cols = width.saturating_sub(tick_w + 2).max(4);
+
+        let mut canvas = BrailleCanvas::new(cols, rows);
+        let dx = (canvas.dot_width() - 1) as f64;
+        let dy = (canvas.dot_height() - 1) as f64;
+        let limit = (canvas.dot_width() + canvas.dot_height()) as f64 * 4.0;
+        let to_dot = |&(x, y): &(f64, f64)| -> Option<(i64, i64)> {
+            let x = self.x.scale.apply(x)?;
+            let y = self.y.scale.apply(y)?;
+            let px = ((x - x_min) / (x_max - x_min) * dx).round();
+            let py = ((y_max - y) / (y_max - y_min) * dy).round();
+            Some((
+                px.clamp(-limit, limit) as i64,
+                py.clamp(-limit, limit) as i64,
+            ))
+        };
+        for (i, series) in self.series.iter().enumerate() {
+            let dots: Vec<Option<(i64, i64)>> = series.points.iter().map(to_dot).collect();
+            match series.kind {
+                Kind::Scatter => dots
+                    .iter()
+                    .flatten()
+                    .for_each(|&(x, y)| canvas.set(x, y, i)),
+                Kind::Line => {
+                    for pair in dots.windows(2) {
+                        if let [Some(a), Some(b)] = pair {
+                            canvas.line(*a, *b, i);
+                        }
+                    }
+                    if let [Some((x, y))] = dots.as_slice() {
+                        canvas.set(*x, *y, i);
+                    }
+                }
+            }
+        }
+
+        let mut output = self.title.as_deref().map(str_title).unwrap_or_default();
+        if let Some(label) = &self.y_label {
+            output.push_str(&format!("{}\n", label.cinfo()));
+        }
+        for (r, row) in canvas.render_rows().iter().enumerate() {
+            let tick = match r {
+                0 => y_ticks[0].as_str(),
+                r if r == rows / 2 && rows > 2 => y_ticks[1].as_str(),
+                r if r == rows - 1 => y_ticks[2].as_str(),
+                _ => "",
+            };
+            let axis = if tick.is_empty() { "│" } else { "┤" };
+            output.push_str(&format!(
+                "{} {}{}\n",
+                format!("{:>tick_w$}", tick).as_str().cvar(),
+                axis.cline(),
+                row
+            ));
+        }
+        output.push_str(&format!(
+            "{} {}\n",
+            " ".repeat(tick_w),
+            format!("└{}", "─".repeat(cols)).cline()
+        ));
+
+        let x_ticks = [x_min, (x_min + x_max) / 2.0, x_max].map(|v| self.x.tick(v, x_max - x_min));
+        let mut ticks = vec![' '; cols + 1];
+        let last = x_ticks[2].chars().count();
+        let mid = x_ticks[1].chars().count();
+        for (pos, tick) in [
+            (0, &x_ticks[0]),
+            ((cols + 1).saturating_sub(mid) / 2, &x_ticks[1]),
+            ((cols + 1).saturating_sub(last), &x_ticks[2]),
+        ] {
+            // Ticks wider than the axis are cut off at its end
+            for (i, c) in tick.chars().enumerate() {
+                if let Some(slot) = ticks.get_mut(pos + i) {
+                    *slot = c;
+                }
+            }
+        }
+        let ticks: String = ticks.into_iter().collect();
+        output.push_str(&format!(
+            "{} {}\n",
+            " ".repeat(tick_w),
+            ticks.trim_end().cvar()
+        ));
+        if let Some(label) = &self.x_label {
+            output.push_str(&format!(
+                "{} {}\n",
+                " ".repeat(tick_w),
+                center(label, cols + 1).trim_end().cinfo()
+            ));
+        }
+        if self.series.len() > 1 || self.series.iter().any(|s| !s.name.is_empty()) {
+            let names: Vec<&str> = self.series.iter().map(|s| s.name.as_str()).collect();
+            output.push_str(&str_legend(&names, tick_w + 2));
+        }
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn narrow_plot_with_wide_ticks() {
+        let points = [(0.000123, 1.0), (0.000456, 2.0), (0.000789, 3.0)];
+        for width in 0..12 {
+            let output = Plot::new().line("", &points).width(width).render();
+            assert!(output.contains('└'), "{}", output);
+        }
+    }
+
+    #[test]
+    fn zero_sized_canvas() {
+        let mut canvas = BrailleCanvas::new(0, 2);
+        canvas.line((0, 0), (5, 7), 0);
+        let rows = canvas.render_rows();
+        assert_eq!(rows.len(), 2);
+        assert!(rows.iter().all(|r| crate::printer::display_width(r) == 1));
+        assert!(BrailleCanvas::new(0, 0).render_rows().is_empty());
+        for height in 0..3 {
+            let points = [(1.0, 1.0), (2.0, 4.0)];
+            Plot::new()
+                .scatter("", &points)
+                .width(0)
+                .height(height)
+                .render();
+        }
+    }
+
+    #[test]
+    fn line_sets_both_ends() {
+        let mut canvas = BrailleCanvas::new(2, 1);
+        canvas.line((0, 0), (3, 3), 0);
+        let rows = canvas.render_rows();
+        assert_eq!(crate::printer::strip_ansi(&rows[0]), "⠑⢄");
+    }
+}
@@ -0,0 +1,265 @@
+use crate::color::{detect_color_support, fg_code, gradient_at, ColorSupport};
+use crate::graph::{center, str_value, Chart};
+use crate::printer::colors_enabled;
+use crate::termprint::{get_terminal_width, str_title, ColoredItem, RESET};
+
+// Shades used when colors are off
+const SHADES: [&str; 4] = ["░", "▒", "▓", "█"];
+const BLOCK: &str = "█";
+const LEGEND_STEPS: usize = 16;
+
+// Perceptually ordered dark-to-bright default gradient
+pub const VIRIDIS: [(u8, u8, u8); 5] = [
+    (68, 1, 84),
+    (59, 82, 139),
+    (33, 145, 140),
+    (94, 201, 98),
+    (253, 231, 37),
+];
+
+// Colored matrix of values, e.g. weekday x hour-of-day activity. Cells use
+// truecolor, the nearest 256-color index or the nearest of the 16 ANSI colors
+// depending on the terminal; NaN cells stay empty.
+#[derive(Clone, Debug)]
+pub struct Heatmap {
+    values: Vec<Vec<f64>>,
+    row_labels: Vec<String>,
+    column_labels: Vec<String>,
+    title: Option<String>,
+    range: Option<(f64, f64)>,
+    gradient: Vec<(u8, u8, u8)>,
+    support: Option<ColorSupport>,
+    cell_width: Option<usize>,
+    width: Option<usize>,
+}
+
+impl Heatmap {
+    pub fn new(values: &[Vec<f64>]) -> Self {
+        Heatmap {
+            values: values.to_vec(),
+            row_labels: vec![],
+            column_labels: vec![],
+            title: None,
+            range: None,
+            gradient: VIRIDIS.to_vec(),
+            support: None,
+            cell_width: None,
+            width: None,
+        }
+    }
+
+    pub fn row_labels(mut self, labels: &[&str]) -> Self {
+        self.row_labels = labels.iter().map(|l| l.to_string()).collect();
+        self
+    }
+
+    pub fn column_labels(mut self, labels: &[&str]) -> Self {
+        self.column_labels = labels.iter().map(|l| l.to_string()).collect();
+        self
+    }
+
+    pub fn title(mut self, title: &str) -> Self {
+        self.title = Some(title.to_string());
+        self
+    }
+
+    // Fixed value range instead of the matrix min and max
+    pub fn range(mut self, min: f64, max: f64) -> Self {
+        self.range = Some((min, max));
+        self
+    }
+
+    // Color stops from the lowest to the highest value
+    pub fn gradient(mut self, stops: &[(u8, u8, u8)]) -> Self {
+        if !stops.is_empty() {
+            self.gradient = stops.to_vec();
+        }
+        self
+    }
+
+    // Overrides detect_color_support()
+    pub fn color_support(mut self, support: ColorSupport) -> Self {
+        self.support = Some(support);
+        self
+    }
+
+    pub fn cell_width(mut self, width: usize) -> Self {
+        self.cell_width = Some(width.max(1));
+        self
+    }
+
+    pub fn width(mut self, width: usize) -> Self {
+        self.width = Some(width);
+        self
+    }
+
+    fn bounds(&self) -> (f64, f64) {
+        self.range.unwrap_or_else(|| {
+            self.values
+                .iter()
+                .flatten()
+                .filter(|v| v.is_finite())
+                .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), &v| {
+                    (lo.min(v), hi.max(v))
+                })
+        })
+    }
+
+    fn cell(&self, t: Option<f64>, support: ColorSupport, width: usize) -> String {
+        let Some(t) = t else {
+            return " ".repeat(width);
+        };
+        if !colors_enabled() {
+            let shade = (t.clamp(0.0, 1.0) * (SHADES.len() - 1) as f64).round() as usize;
+            return SHADES[shade].repeat(width);
+        }
+        let (r, g, b) = gradient_at(&self.gradient, t);
+        format!(
+            "\x1b[{}m{}{RESET}",
+            fg_code(r, g, b, support),
+            BLOCK.repeat(width)
+        )
+    }
+}
+
+impl Chart for Heatmap {
+    fn render(&self) -> String {
+        let width = self.width.unwrap_or_else(get_terminal_width);
+        let support = self.support.unwrap_or_else(detect_color_support);
+        let (min, max) = self.bounds();
+        let normalize = |v: f64| {
+            if !v.is_finite() || !min.is_finite() {
+                None
+            } else if max > min {
+                Some((v - min) / (max - min))
+            } else {
+                Some(0.5)
+            }
+        };
+
+        let cols = self.values.iter().map(|r| r.len()).max().unwrap_or(0);
+        let row_w = self
+            .row_labels
+            .iter()
+            .map(|l| l.chars().count())
+            .max()
+            .unwrap_or(0);
+        let label_w = self
+            .column_labels
+            .iter()
+            .map(|l| l.chars().count())
+            .max()
+            .unwrap_or(0);
+        // Keep a space between column labels once they fill a cell
+        let mut cell_w = self
+            .cell_width
+            .unwrap_or(if label_w < 2 { 2 } else { label_w + 1 });
+        if cols > 0 && row_w + 1 + cols * cell_w > width {
+            cell_w = (width.saturating_sub(row_w + 1) / cols).max(1);
+        }
+        let indent = " ".repeat(row_w + 1);
+
+        let mut output = self.title.as_deref().map(str_title).unwrap_or_default();
+        if !self.column_labels.is_empty() {
+            let header: String = (0..cols)
+                .map(|c| center(self.column_labels.get(c).map_or("", |l| l), cell_w))
+                .collect();
+            output.push_str(&format!("{}{}\n", indent, header.trim_end().cinfo()));
+        }
+        for (r, row) in self.values.iter().enumerate() {
+            let label = self.row_labels.get(r).map_or("", |l| l.as_str());
+            output.push_str(&format!(
+                "{} ",
+                format!("{:>row_w$}", label).as_str().cinfo()
+            ));
+            for c in 0..cols {
+                let value = row.get(c).copied().unwrap_or(f64::NAN);
+                output.push_str(&self.cell(normalize(value), support, cell_w));
+            }
+            output.push('\n');
+        }
+
+        if min.is_finite() && max.is_finite() {
+            let scale: String = (0..LEGEND_STEPS)
+                .map(|i| self.cell(Some(i as f64 / (LEGEND_STEPS - 1) as f64), support, 1))
+                .collect();
+            output.push_str(&format!(
+                "\n{}{} {} {}\n",
+                indent,
+                str_value(min).cvar(),
+                scale,
+                str_value(max).cvar()
+            ));
+        }
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::printer::{strip_ansi, ColorPolicy, Printer};
+
+    // Without colors cells are drawn with shades
+    fn render(heatmap: Heatmap) -> String {
+        let output = Printer::new(Vec::new())
+            .color(ColorPolicy::Never)
+            .render(|| heatmap.render());
+        strip_ansi(&output)
+    }
+
+    #[test]
+    fn empty_matrix() {
+        assert_eq!(render(Heatmap::new(&[]).width(40)), "");
+        assert_eq!(render(Heatmap::new(&[vec![], vec![]]).width(40)), " \n \n");
+    }
+
+    #[test]
+    fn constant_values_get_the_middle_shade() {
+        let output = render(Heatmap::new(&[vec![0.0, 0.0], vec![0.0, 0.0]]).width(40));
+        assert_eq!(output.lines().take(2).collect::<Vec<_>>(), [" ▓▓▓▓"; 2]);
+        assert!(
+            output.ends_with("\n\n 0 ░░░▒▒▒▒▒▓▓▓▓▓███ 0\n"),
+            "{}",
+            output
+        );
+    }
+
+    #[test]
+    fn negative_values_scale_from_the_minimum() {
+        let output = render(
+            Heatmap::new(&[vec![-10.0, 0.0, 10.0], vec![f64::NAN, -10.0]])
+                .row_labels(&["a", "bb"])
+                .width(40),
+        );
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines[0], " a ░░▓▓██");
+        // Missing and NaN cells stay empty
+        assert_eq!(lines[1], "bb   ░░  ");
+        assert!(lines[3].starts_with("   -10 ░"), "{}", output);
+        assert!(lines[3].ends_with("█ 10"), "{}", output);
+    }
+
+    #[test]
+    fn cells_shrink_to_the_width() {
+        let values = vec![(0..10).map(f64::from).collect::<Vec<_>>()];
+        let labels: Vec<String> = (0..10).map(|i| format!("h{}", i)).collect();
+        let labels: Vec<&str> = labels.iter().map(|l| l.as_str()).collect();
+
+        // Labels of two characters get cells of three
+        let wide = render(Heatmap::new(&values).column_labels(&labels).width(80));
+        assert_eq!(wide.lines().nth(1).unwrap().chars().count(), 1 + 10 * 3);
+
+        for width in [21, 12, 5] {
+            let output = render(Heatmap::new(&values).column_labels(&labels).width(width));
+            let row = output.lines().nth(1).unwrap();
+            assert_eq!(row.chars().count(), 1 + 10 * ((width - 1) / 10).max(1));
+        }
+        assert_eq!(
+            render(Heatmap::new(&values).cell_width(1).width(80))
+                .lines()
+                .next(),
+            Some(" ░░▒▒▒▓▓▓██")
+        );
+    }
+}
@@ -0,0 +1,325 @@
+use crate::graph::{hbar, str_value, Chart};
+use crate::termprint::{get_terminal_width, message, str_key_value, str_title, ColoredItem};
+
+const DEFAULT_BINS: usize = 10;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Binning {
+    // Bins of equal width between min and max
+    Linear(usize),
+    // Bins of equal ratio, for long-tailed samples such as latencies.
+    // Samples that are not positive are counted in a separate "≤ 0" bin.
+    Log(usize),
+    // Bins holding roughly the same number of samples
+    Quantile(usize),
+}
+
+impl Default for Binning {
+    fn default() -> Self {
+        Binning::Linear(DEFAULT_BINS)
+    }
+}
+
+// Histogram of a numeric sample with counts and percentages per bin and a
+// count/min/p50/p90/p99/max summary below.
+#[derive(Clone, Debug)]
+pub struct Histogram {
+    samples: Vec<f64>,
+    binning: Binning,
+    title: Option<String>,
+    width: Option<usize>,
+    summary: bool,
+}
+
+impl Histogram {
+    // Non-finite samples are ignored
+    pub fn new(samples: &[f64]) -> Self {
+        let mut samples: Vec<f64> = samples.iter().copied().filter(|v| v.is_finite()).collect();
+        samples.sort_by(f64::total_cmp);
+        Histogram {
+            samples,
+            binning: Binning::default(),
+            title: None,
+            width: None,
+            summary: true,
+        }
+    }
+
+    pub fn binning(mut self, binning: Binning) -> Self {
+        self.binning = binning;
+        self
+    }
+
+    pub fn title(mut self, title: &str) -> Self {
+        self.title = Some(title.to_string());
+        self
+    }
+
+    pub fn width(mut self, width: usize) -> Self {
+        self.width = Some(width);
+        self
+    }
+
+    pub fn summary(mut self, show: bool) -> Self {
+        self.summary = show;
+        self
+    }
+
+    // Samples spread over the bins: all of them, only the positive ones
+    // with log binning
+    fn binned(&self) -> &[f64] {
+        match self.binning {
+            Binning::Log(_) => &self.samples[self.non_positive()..],
+            _ => &self.samples,
+        }
+    }
+
+    // Samples left out of log bins
+    fn non_positive(&self) -> usize {
+        match self.binning {
+            Binning::Log(_) => self.samples.partition_point(|v| *v <= 0.0),
+            _ => 0,
+        }
+    }
+
+    // Bin edges, a single edge when all binned samples are equal
+    fn edges(&self) -> Vec<f64> {
+        let samples = self.binned();
+        let (Some(&min), Some(&max)) = (samples.first(), samples.last()) else {
+            return vec![];
+        };
+        let mut edges: Vec<f64> = match self.binning {
+            Binning::Linear(n) => {
+                let n = n.max(1);
+                (0..=n)
+                    .map(|i| min + (max - min) * i as f64 / n as f64)
+                    .collect()
+            }
+            Binning::Log(n) => {
+                let n = n.max(1);
+                (0..=n)
+                    .map(|i| min * (max / min).powf(i as f64 / n as f64))
+                    .collect()
+            }
+            Binning::Quantile(n) => {
+                let n = n.max(1);
+                (0..=n)
+                    .map(|i| quantile(samples, i as f64 / n as f64))
+                    .collect()
+            }
+        };
+        edges.dedup();
+        edges
+    }
+
+    fn counts(&self, edges: &[f64]) -> Vec<usize> {
+        if edges.is_empty() {
+            return vec![];
+        }
+        let bins = edges.len().saturating_sub(1).max(1);
+        let mut counts = vec![0; bins];
+        for v in self.binned() {
+            // Bins are [lo, hi), the last one includes max
+            let bin = edges.partition_point(|e| e <= v).saturating_sub(1);
+            counts[bin.min(bins - 1)] += 1;
+        }
+        counts
+    }
+}
+
+impl Chart for Histogram {
+    fn render(&self) -> String {
+        let mut output = self.title.as_deref().map(str_title).unwrap_or_default();
+        if self.samples.is_empty() {
+            output.push_str(&format!("{}\n", message("no samples")));
+            return output;
+        }
+        let width = self.width.unwrap_or_else(get_terminal_width);
+        let edges = self.edges();
+        let mut labels: Vec<String> = match edges.len() {
+            1 => vec![str_value(edges[0])],
+            _ => edges
+                .windows(2)
+                .map(|e| format!("{} – {}", str_value(e[0]), str_value(e[1])))
+                .collect(),
+        };
+        let mut counts = self.counts(&edges);
+        let non_positive = self.non_positive();
+        if non_positive > 0 {
+            labels.insert(0, "≤ 0".to_string());
+            counts.insert(0, non_positive);
+        }
+        let total = self.samples.len();
+        let max_count = counts.iter().copied().max().unwrap_or(1).max(1);
+
+        let label_w = labels.iter().map(|l| l.chars().count()).max().unwrap_or(0);
+        let count_w = max_count.to_string().len();
+        // label, " │", bar, " ", count, " ", "100.0%"
+        let bar_w = width.saturating_sub(label_w + count_w + 10).max(1);
+
+        for (label, count) in labels.iter().zip(&counts) {
+            let bar = hbar(*count as f64, max_count as f64, bar_w);
+            let pct = format!("{:.1}%", *count as f64 * 100.0 / total as f64);
+            output.push_str(&format!(
+                "{} {}{}{} {} {}\n",
+                format!("{:>label_w$}", label).as_str().cinfo(),
+                "│".cline(),
+                bar.citem(),
+                " ".repeat(bar_w - bar.chars().count()),
+                format!("{:>count_w$}", count).as_str().cvar(),
+                format!("{:>6}", pct).as_str().cvar()
+            ));
+        }
+        if self.summary {
+            output.push('\n');
+            output.push_str(&str_summary_sorted(&self.samples));
+        }
+        output
+    }
+}
+
+// Nearest-rank quantile of sorted values, q in 0..=1.
+pub fn quantile(sorted: &[f64], q: f64) -> f64 {
+    if sorted.is_empty() {
+        return f64::NAN;
+    }
+    let rank = (q.clamp(0.0, 1.0) * sorted.len() as f64).ceil() as usize;
+    sorted[rank.saturating_sub(1).min(sorted.len() - 1)]
+}
+
+fn str_summary_sorted(sorted: &[f64]) -> String {
+    let rows = [
+        ("count", sorted.len().to_string()),
+        ("min", str_value(quantile(sorted, 0.0))),
+        ("p50", str_value(quantile(sorted, 0.5))),
+        ("p90", str_value(quantile(sorted, 0.9))),
+        ("p99", str_value(quantile(sorted, 0.99))),
+        ("max", str_value(quantile(sorted, 1.0))),
+    ];
+    let max_v = rows.iter().map(|(_, v)| v.len()).max().unwrap_or(1);
+    rows.iter()
+        .map(|(k, v)| str_key_value(k, v, 5, max_v))
+        .collect()
+}
+
+// count/min/p50/p90/p99/max of a sample, non-finite values ignored.
+pub fn str_summary(samples: &[f64]) -> String {
+    let mut sorted: Vec<f64> = samples.iter().copied().filter(|v| v.is_finite()).collect();
+    sorted.sort_by(f64::total_cmp);
+    str_summary_sorted(&sorted)
+}
+
+pub fn str_histogram(samples: &[f64], binning: Binning) -> String {
+    Histogram::new(samples).binning(binning).render()
+}
+
+pub fn print_histogram(samples: &[f64], binning: Binning) {
+    Histogram::new(samples).binning(binning).print();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::printer::strip_ansi;
+
+    fn approx(a: &[f64], b: &[f64]) -> bool {
+        a.len() == b.len() && a.iter().zip(b).all(|(a, b)| (a - b).abs() < 1e-9)
+    }
+
+    #[test]
+    fn quantile_is_nearest_rank() {
+        let sorted = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 10.0];
+        assert_eq!(quantile(&sorted, 0.0), 1.0);
+        assert_eq!(quantile(&sorted, 0.5), 5.0);
+        assert_eq!(quantile(&sorted, 0.51), 6.0);
+        assert_eq!(quantile(&sorted, 0.9), 9.0);
+        assert_eq!(quantile(&sorted, 0.99), 10.0);
+        assert_eq!(quantile(&sorted, 1.0), 10.0);
+        assert_eq!(quantile(&sorted, 7.0), 10.0);
+        assert_eq!(quantile(&[42.0], 0.5), 42.0);
+        assert!(quantile(&[], 0.5).is_nan());
+    }
+
+    #[test]
+    fn linear_edges_and_counts() {
+        let histogram =
+            Histogram::new(&[0.0, 1.0, 2.0, 5.0, 9.9, 10.0]).binning(Binning::Linear(4));
+        let edges = histogram.edges();
+        assert!(approx(&edges, &[0.0, 2.5, 5.0, 7.5, 10.0]), "{:?}", edges);
+        // Bins are [lo, hi), the last one includes max
+        assert_eq!(histogram.counts(&edges), vec![3, 0, 1, 2]);
+    }
+
+    // Label and count of each bin
+    fn rows(histogram: Histogram) -> Vec<(String, usize)> {
+        let output = strip_ansi(&histogram.summary(false).width(40).render());
+        output
+            .lines()
+            .map(|l| {
+                let (label, rest) = l.split_once('│').unwrap();
+                let count = rest.split_whitespace().rev().nth(1).unwrap();
+                (label.trim().to_string(), count.parse().unwrap())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn log_edges_skip_non_positive_samples() {
+        let histogram =
+            Histogram::new(&[-1.0, 0.0, 1.0, 10.0, 100.0, 1000.0]).binning(Binning::Log(3));
+        let edges = histogram.edges();
+        assert!(approx(&edges, &[1.0, 10.0, 100.0, 1000.0]), "{:?}", edges);
+        assert_eq!(histogram.counts(&edges), vec![1, 1, 2]);
+        assert_eq!(histogram.non_positive(), 2);
+    }
+
+    #[test]
+    fn non_positive_samples_get_their_own_log_bin() {
+        let histogram =
+            Histogram::new(&[-1.0, 0.0, 1.0, 10.0, 100.0, 1000.0]).binning(Binning::Log(3));
+        assert_eq!(
+            rows(histogram),
+            vec![
+                ("≤ 0".to_string(), 2),
+                ("1 – 10".to_string(), 1),
+                ("10 – 100".to_string(), 1),
+                ("100 – 1000".to_string(), 2),
+            ]
+        );
+
+        let histogram = Histogram::new(&[-5.0, 0.0]).binning(Binning::Log(3));
+        assert_eq!(rows(histogram), vec![("≤ 0".to_string(), 2)]);
+        let histogram = Histogram::new(&[0.0, 5.0]).binning(Binning::Log(3));
+        assert_eq!(
+            rows(histogram),
+            vec![("≤ 0".to_string(), 1), ("5".to_string(), 1)]
+        );
+    }
+
+    #[test]
+    fn quantile_edges_are_deduplicated() {
+        let histogram =
+            Histogram::new(&[1.0, 1.0, 1.0, 1.0, 2.0, 3.0, 4.0, 5.0]).binning(Binning::Quantile(4));
+        let edges = histogram.edges();
+        assert_eq!(edges, vec![1.0, 3.0, 5.0]);
+        assert_eq!(histogram.counts(&edges), vec![5, 3]);
+    }
+
+    #[test]
+    fn constant_sample_gets_one_bin() {
+        let histogram = Histogram::new(&[3.0, 3.0, f64::NAN, f64::INFINITY]);
+        let edges = histogram.edges();
+        assert_eq!(edges, vec![3.0]);
+        assert_eq!(histogram.counts(&edges), vec![2]);
+    }
+
+    #[test]
+    fn single_values_are_not_labeled_as_ranges() {
+        for binning in [Binning::Linear(10), Binning::Log(10), Binning::Quantile(4)] {
+            let histogram = Histogram::new(&[5.0]).binning(binning);
+            assert_eq!(rows(histogram), vec![("5".to_string(), 1)], "{:?}", binning);
+        }
+        let histogram = Histogram::new(&[0.5, 0.5, 0.5]);
+        assert_eq!(rows(histogram), vec![("0.5".to_string(), 3)]);
+    }
+}
@@ -0,0 +1,80 @@
+use crate::graph::VBLOCKS;
+
+const GAP: char = ' ';
+
+// One block per value, scaled between the smallest and largest finite value.
+// NaN (or any non-finite value) leaves a gap. The result is plain text, so
+// it can be used as a table cell or key/value value.
+pub fn str_sparkline(values: &[f64]) -> String {
+    let (min, max) = values
+        .iter()
+        .filter(|v| v.is_finite())
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), &v| {
+            (lo.min(v), hi.max(v))
+        });
+    str_sparkline_range(values, min, max)
+}
+
+// Same with a fixed range, e.g. to compare several sparklines. Values
+// outside the range are clamped.
+pub fn str_sparkline_range(values: &[f64], min: f64, max: f64) -> String {
+    values
+        .iter()
+        .map(|&v| {
+            if !v.is_finite() {
+                return GAP;
+            }
+            let level = if max > min {
+                ((v - min) / (max - min) * 7.0).round().clamp(0.0, 7.0) as usize
+            } else {
+                3
+            };
+            VBLOCKS[level + 1].chars().next().unwrap_or(GAP)
+        })
+        .collect()
+}
+
+// For series with missing samples.
+pub fn str_sparkline_opt(values: &[Option<f64>]) -> String {
+    let values: Vec<f64> = values.iter().map(|v| v.unwrap_or(f64::NAN)).collect();
+    str_sparkline(&values)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn one_block_per_value_between_min_and_max() {
+        assert_eq!(
+            str_sparkline(&[1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0]),
+            "▁▂▃▄▅▆▇█"
+        );
+        assert_eq!(str_sparkline(&[0.0, 8.0, 4.0]), "▁█▅");
+        assert_eq!(str_sparkline(&vec![1.0; 50]).chars().count(), 50);
+    }
+
+    #[test]
+    fn empty_and_constant_data() {
+        assert_eq!(str_sparkline(&[]), "");
+        assert_eq!(str_sparkline(&[0.0, 0.0, 0.0]), "▄▄▄");
+        assert_eq!(str_sparkline(&[5.0]), "▄");
+        assert_eq!(str_sparkline(&[f64::NAN, f64::NAN]), "  ");
+    }
+
+    #[test]
+    fn negative_values_scale_from_the_minimum() {
+        assert_eq!(str_sparkline(&[-7.0, 0.0]), "▁█");
+        assert_eq!(str_sparkline(&[-3.0, -2.0, -1.0]), "▁▅█");
+    }
+
+    #[test]
+    fn gaps_and_fixed_ranges() {
+        assert_eq!(str_sparkline(&[1.0, f64::NAN, f64::INFINITY, 8.0]), "▁  █");
+        assert_eq!(str_sparkline_opt(&[Some(1.0), None, Some(8.0)]), "▁ █");
+        assert_eq!(
+            str_sparkline_range(&[-10.0, 0.0, 7.0, 100.0], 0.0, 7.0),
+            "▁▁██"
+        );
+    }
+}
@@ -0,0 +1,264 @@
+use crate::panel::wrap;
+use crate::printer::{display_width, Printer};
+use crate::termprint::{get_terminal_width, RESET};
+
+const DEFAULT_GUTTER: usize = 3;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ColumnWidth {
+    // Width of the widest line, shrunk if all columns do not fit
+    #[default]
+    Auto,
+    Fixed(usize),
+    // Shares the width left by the other columns
+    Fill,
+}
+
+// Places rendered blocks (tables, str_hashmap, charts, panels) side by side.
+// Shorter blocks are padded with empty lines, lines wider than their column
+// are word-wrapped.
+#[derive(Clone, Debug)]
+pub struct Columns {
+    blocks: Vec<(String, ColumnWidth)>,
+    gutter: usize,
+    width: Option<usize>,
+}
+
+impl Default for Columns {
+    fn default() -> Self {
+        Columns {
+            blocks: vec![],
+            gutter: DEFAULT_GUTTER,
+            width: None,
+        }
+    }
+}
+
+impl Columns {
+    pub fn new() -> Self {
+        Columns::default()
+    }
+
+    pub fn column(self, block: &str) -> Self {
+        self.column_width(block, ColumnWidth::Auto)
+    }
+
+    pub fn column_width(mut self, block: &str, width: ColumnWidth) -> Self {
+        self.blocks.push((block.to_string(), width));
+        self
+    }
+
+    // Spaces between columns
+    pub fn gutter(mut self, gutter: usize) -> Self {
+        self.gutter = gutter;
+        self
+    }
+
+    // Total width, get_terminal_width() by default
+    pub fn width(mut self, width: usize) -> Self {
+        self.width = Some(width);
+        self
+    }
+
+    fn widths(&self, lines: &[Vec<&str>]) -> Vec<usize> {
+        let total = self.width.unwrap_or_else(get_terminal_width);
+        let gutters = self.gutter * self.blocks.len().saturating_sub(1);
+        let natural: Vec<usize> = lines
+            .iter()
+            .map(|l| l.iter().map(|l| display_width(l)).max().unwrap_or(0))
+            .collect();
+        let fixed: usize = self
+            .blocks
+            .iter()
+            .filter_map(|(_, w)| match w {
+                ColumnWidth::Fixed(w) => Some(*w),
+                _ => None,
+            })
+            .sum();
+        let fills = self
+            .blocks
+            .iter()
+            .filter(|(_, w)| *w == ColumnWidth::Fill)
+            .count();
+        let available = total.saturating_sub(gutters + fixed);
+        let auto: usize = self
+            .blocks
+            .iter()
+            .zip(&natural)
+            .filter(|((_, w), _)| *w == ColumnWidth::Auto)
+            .map(|(_, n)| n)
+            .sum();
+        // Auto columns leave at least one cell per Fill column
+        let auto_space = available.saturating_sub(fills);
+        let scale = if auto > auto_space && auto > 0 {
+            auto_space as f64 / auto as f64
+        } else {
+            1.0
+        };
+        let auto_used: usize = self
+            .blocks
+            .iter()
+            .zip(&natural)
+            .filter(|((_, w), _)| *w == ColumnWidth::Auto)
+            .map(|(_, n)| (*n as f64 * scale) as usize)
+            .sum();
+        let fill = available.saturating_sub(auto_used) / fills.max(1);
+
+        self.blocks
+            .iter()
+            .zip(&natural)
+            .map(|((_, w), n)| match w {
+                ColumnWidth::Auto => ((*n as f64 * scale) as usize).max(1),
+                ColumnWidth::Fixed(w) => (*w).max(1),
+                ColumnWidth::Fill => fill.max(1),
+            })
+            .collect()
+    }
+
+    pub fn render(&self) -> String {
+        let lines: Vec<Vec<&str>> = self
+            .blocks
+            .iter()
+            .map(|(block, _)| block.trim_end_matches('\n').lines().collect())
+            .collect();
+        let widths = self.widths(&lines);
+        let columns: Vec<Vec<String>> = lines
+            .iter()
+            .zip(&widths)
+            .map(|(lines, &width)| {
+                lines
+                    .iter()
+                    .flat_map(|line| {
+                        if display_width(line) <= width {
+                            vec![line.to_string()]
+                        } else {
+                            wrap(line, width)
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+        let height = columns.iter().map(|c| c.len()).max().unwrap_or(0);
+
+        let gutter = " ".repeat(self.gutter);
+        let mut output = String::new();
+        for row in 0..height {
+            let mut line = String::new();
+            for (i, (column, &width)) in columns.iter().zip(&widths).enumerate() {
+                if i > 0 {
+                    line.push_str(&gutter);
+                }
+                let cell = column.get(row).map_or("", |c| c.as_str());
+                line.push_str(cell);
+                // Keep colors of a cell from running into the next column
+                if cell.contains('\x1b') && !cell.ends_with(RESET) {
+                    line.push_str(RESET);
+                }
+                line.push_str(&" ".repeat(width.saturating_sub(display_width(cell))));
+            }
+            output.push_str(line.trim_end());
+            output.push('\n');
+        }
+        output
+    }
+
+    pub fn print(&self) {
+        let _ = Printer::stdout().columns(self);
+    }
+}
+
+// Blocks side by side with their natural widths.
+pub fn str_columns(blocks: &[&str], gutter: usize) -> String {
+    blocks
+        .iter()
+        .fold(Columns::new().gutter(gutter), |c, b| c.column(b))
+        .render()
+}
+
+pub fn print_columns(blocks: &[&str], gutter: usize) {
+    blocks
+        .iter()
+        .fold(Columns::new().gutter(gutter), |c, b| c.column(b))
+        .print();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::printer::strip_ansi;
+
+    // Display column at which `needle` starts in each line that has it
+    fn columns_of(output: &str, needle: &str) -> Vec<usize> {
+        output
+            .lines()
+            .filter_map(|l| l.find(needle).map(|i| display_width(&l[..i])))
+            .collect()
+    }
+
+    #[test]
+    fn shorter_blocks_are_padded() {
+        assert_eq!(str_columns(&["a\nbb\nc", "x"], 2), "a   x\nbb\nc\n");
+        assert_eq!(str_columns(&["a", "x\ny"], 2), "a  x\n   y\n");
+        assert_eq!(str_columns(&[], 2), "");
+    }
+
+    #[test]
+    fn fixed_and_fill_widths() {
+        let output = Columns::new()
+            .width(30)
+            .gutter(2)
+            .column_width("left", ColumnWidth::Fixed(10))
+            .column_width("mid", ColumnWidth::Fill)
+            .column("r")
+            .render();
+        assert_eq!(columns_of(&output, "mid"), [12]);
+        assert_eq!(columns_of(&output, "r"), [29]);
+
+        // Fill columns share what is left evenly
+        let output = Columns::new()
+            .width(24)
+            .gutter(2)
+            .column_width("a", ColumnWidth::Fill)
+            .column_width("b", ColumnWidth::Fill)
+            .column_width("c", ColumnWidth::Fixed(2))
+            .render();
+        assert_eq!(columns_of(&output, "b"), [11]);
+        assert_eq!(columns_of(&output, "c"), [22]);
+
+        // Text wider than a fixed column wraps
+        let output = Columns::new()
+            .width(40)
+            .column_width("hello world", ColumnWidth::Fixed(5))
+            .column("x")
+            .render();
+        assert_eq!(output, "hello   x\nworld\n");
+    }
+
+    #[test]
+    fn auto_columns_shrink_to_the_width() {
+        let output = Columns::new()
+            .width(23)
+            .gutter(3)
+            .column(&"word ".repeat(4))
+            .column(&"ward ".repeat(4))
+            .render();
+        assert!(output.lines().all(|l| display_width(l) <= 23), "{}", output);
+        // Both columns get half of the 20 cells left by the gutter
+        assert_eq!(columns_of(&output, "ward"), [13, 13]);
+    }
+
+    #[test]
+    fn colored_blocks_are_padded_by_visible_width() {
+        let output = str_columns(&["\x1b[31mred\x1b[0m\nplain\n\x1b[32mgreen", "X\nY\nZ"], 1);
+        assert_eq!(columns_of(&output, "X"), [6]);
+        assert_eq!(columns_of(&output, "Y"), [6]);
+        assert_eq!(columns_of(&output, "Z"), [6]);
+        // Colors that are not reset end with their cell
+        assert!(
+            output.contains(&format!("green{} Z", RESET)),
+            "{:?}",
+            output
+        );
+        assert_eq!(strip_ansi(&output), "red   X\nplain Y\ngreen Z\n");
+    }
+}
@@ -1,9 +1,27 @@
 mod termprint;
 pub use termprint::*;
 
+mod color;
+mod exchange;
+mod export;
+mod layout;
+#[cfg(feature = "log")]
+mod log_ext;
+mod logger;
+#[cfg(feature = "middleware")]
+mod middleware;
+mod panel;
+mod printer;
+mod progress;
+mod redact;
+mod report;
 mod request_ext;
+mod spinner;
+mod theme;
+#[cfg(feature = "tracing")]
+mod tracing_ext;
 
-// pub mod graph;
+pub mod graph;
 
 #[cfg(test)]
 mod tests {
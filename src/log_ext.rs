@@ -0,0 +1,198 @@
+use std::io::{self, Write};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use log::{LevelFilter, Log, Metadata, Record, SetLoggerError};
+
+use crate::logger::{log_config, Level, LogConfig, TargetFilter};
+use crate::printer::Printer;
+
+pub const RUST_LOG_ENV: &str = "RUST_LOG";
+
+impl From<log::Level> for Level {
+    fn from(level: log::Level) -> Self {
+        match level {
+            log::Level::Trace => Level::Trace,
+            log::Level::Debug => Level::Debug,
+            log::Level::Info => Level::Info,
+            log::Level::Warn => Level::Warn,
+            log::Level::Error => Level::Error,
+        }
+    }
+}
+
+impl From<Level> for LevelFilter {
+    fn from(level: Level) -> Self {
+        match level {
+            Level::Trace => LevelFilter::Trace,
+            Level::Debug => LevelFilter::Debug,
+            Level::Info => LevelFilter::Info,
+            Level::Warn => LevelFilter::Warn,
+            Level::Error => LevelFilter::Error,
+        }
+    }
+}
+
+// log::Log implementation rendering records like `tp_log!`. Filtering follows
+// RUST_LOG, targets are padded to the longest one seen so far. Warnings and
+// errors go to stderr, everything else to stdout, unless a Printer is given
+// with `printer`.
+pub struct TermprintLogger<W: Write = io::Stdout> {
+    filter: TargetFilter,
+    config: LogConfig,
+    target_width: AtomicUsize,
+    printer: Option<Arc<Mutex<Printer<W>>>>,
+}
+
+impl Default for TermprintLogger {
+    fn default() -> Self {
+        TermprintLogger::new()
+    }
+}
+
+impl TermprintLogger {
+    pub fn new() -> Self {
+        TermprintLogger {
+            filter: TargetFilter::from_env(RUST_LOG_ENV)
+                .unwrap_or_else(|| TargetFilter::new(Level::Info)),
+            config: log_config(),
+            target_width: AtomicUsize::new(0),
+            printer: None,
+        }
+    }
+}
+
+impl<W: Write + Send + 'static> TermprintLogger<W> {
+    // All records go to this printer, e.g. a shared Printer::new(Vec::new())
+    // to capture them
+    pub fn printer<V: Write>(self, printer: Arc<Mutex<Printer<V>>>) -> TermprintLogger<V> {
+        TermprintLogger {
+            filter: self.filter,
+            config: self.config,
+            target_width: self.target_width,
+            printer: Some(printer),
+        }
+    }
+
+    pub fn filter(mut self, spec: &str) -> Self {
+        self.filter = TargetFilter::parse(spec);
+        self
+    }
+
+    pub fn config(mut self, config: LogConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    pub fn init(self) -> Result<(), SetLoggerError> {
+        let max_level = self
+            .filter
+            .max_verbosity()
+            .map_or(LevelFilter::Off, LevelFilter::from);
+        log::set_boxed_logger(Box::new(self))?;
+        log::set_max_level(max_level);
+        Ok(())
+    }
+}
+
+impl<W: Write + Send + 'static> Log for TermprintLogger<W> {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        self.filter
+            .enabled(Level::from(metadata.level()), metadata.target())
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let level = Level::from(record.level());
+        let target = record.target();
+        let width = self
+            .target_width
+            .fetch_max(target.len() + 2, Ordering::Relaxed)
+            .max(target.len() + 2);
+        let config = self.config.clone().target_width(width);
+        let msg = record.args().to_string();
+        let _ = match &self.printer {
+            Some(printer) => match printer.lock() {
+                Ok(mut printer) => printer.log(&config, level, Some(target), &msg),
+                Err(_) => Ok(()),
+            },
+            None if level >= Level::Warn => {
+                Printer::stderr().log(&config, level, Some(target), &msg)
+            }
+            None => Printer::stdout().log(&config, level, Some(target), &msg),
+        };
+    }
+
+    fn flush(&self) {
+        if let Some(printer) = &self.printer {
+            if let Ok(mut printer) = printer.lock() {
+                let _ = printer.flush();
+            }
+        }
+        let _ = std::io::stdout().flush();
+        let _ = std::io::stderr().flush();
+    }
+}
+
+pub fn init_logger() -> Result<(), SetLoggerError> {
+    TermprintLogger::new().init()
+}
+
+#[cfg(test)]
+mod tests {
+    use log::{Metadata, Record};
+
+    use super::*;
+    use crate::printer::ColorPolicy;
+
+    fn metadata(level: log::Level, target: &str) -> Metadata<'_> {
+        Metadata::builder().level(level).target(target).build()
+    }
+
+    #[test]
+    fn enabled_follows_the_filter() {
+        let logger = TermprintLogger::new().filter("warn,app=debug,app::db=off,noisy=nonsense");
+        assert!(logger.enabled(&metadata(log::Level::Debug, "app::http")));
+        assert!(!logger.enabled(&metadata(log::Level::Trace, "app::http")));
+        assert!(!logger.enabled(&metadata(log::Level::Error, "app::db")));
+        assert!(logger.enabled(&metadata(log::Level::Warn, "other")));
+        assert!(!logger.enabled(&metadata(log::Level::Info, "other")));
+        // The invalid directive does not silence the target
+        assert!(logger.enabled(&metadata(log::Level::Warn, "noisy")));
+    }
+
+    #[test]
+    fn records_are_printed_to_the_printer() {
+        let printer = Arc::new(Mutex::new(
+            Printer::new(Vec::new()).color(ColorPolicy::Never),
+        ));
+        let logger = TermprintLogger::new()
+            .filter("info")
+            .config(LogConfig::default().no_timestamp())
+            .printer(printer.clone());
+        for (level, target, msg) in [
+            (log::Level::Error, "app::db", "failed"),
+            (log::Level::Debug, "app", "hidden"),
+            (log::Level::Info, "app", "started"),
+        ] {
+            logger.log(
+                &Record::builder()
+                    .args(format_args!("{}", msg))
+                    .level(level)
+                    .target(target)
+                    .build(),
+            );
+        }
+        logger.flush();
+
+        let output = String::from_utf8(printer.lock().unwrap().get_ref().clone()).unwrap();
+        // Targets are padded to the longest seen so far
+        assert_eq!(
+            output, "ERROR [app::db] failed\nINFO  [app]     started\n",
+            "{:?}",
+            output
+        );
+    }
+}
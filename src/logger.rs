@@ -0,0 +1,362 @@
+use std::fmt;
+use std::str::FromStr;
+use std::sync::RwLock;
+
+use colored::{ColoredString, Colorize};
+use time::format_description::{self, OwnedFormatItem};
+use time::OffsetDateTime;
+
+use crate::printer::Printer;
+use crate::termprint::ColoredItem;
+
+pub const LOG_LEVEL_ENV: &str = "TERMPRINT_LOG";
+// Timestamps are UTC, hence the Z
+pub const TIMESTAMP_FORMAT: &str = "[hour]:[minute]:[second].[subsecond digits:3]Z";
+const DEFAULT_LEVEL: Level = Level::Info;
+
+static LOG_CONFIG: RwLock<Option<LogConfig>> = RwLock::new(None);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Level {
+    Trace,
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+impl Level {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Level::Trace => "TRACE",
+            Level::Debug => "DEBUG",
+            Level::Info => "INFO",
+            Level::Warn => "WARN",
+            Level::Error => "ERROR",
+        }
+    }
+
+    fn badge(&self) -> ColoredString {
+        let badge = format!("{:<5}", self.as_str());
+        let badge = badge.as_str();
+        match self {
+            Level::Trace => badge.cline(),
+            Level::Debug => badge.citem(),
+            Level::Info => badge.cinfo(),
+            Level::Warn => badge.cwarning().bold(),
+            Level::Error => badge.cerror(),
+        }
+    }
+}
+
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for Level {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "trace" => Ok(Level::Trace),
+            "debug" => Ok(Level::Debug),
+            "info" => Ok(Level::Info),
+            "warn" | "warning" => Ok(Level::Warn),
+            "error" => Ok(Level::Error),
+            other => Err(format!("unknown log level: {}", other)),
+        }
+    }
+}
+
+// RUST_LOG style directives: "info,my_crate::net=trace,hyper=off". A bare
+// level sets the default, `target=level` applies to the target and its
+// submodules, the longest matching target wins. Targets without a matching
+// directive use the bare level, Info if there is none. None means off,
+// directives with an unknown level are ignored.
+#[derive(Clone, Debug, Default)]
+pub struct TargetFilter {
+    default: Option<Level>,
+    directives: Vec<(String, Option<Level>)>,
+}
+
+impl TargetFilter {
+    pub fn new(default: Level) -> Self {
+        TargetFilter {
+            default: Some(default),
+            directives: vec![],
+        }
+    }
+
+    pub fn parse(spec: &str) -> Self {
+        let mut filter = TargetFilter::new(DEFAULT_LEVEL);
+        for directive in spec.split(',').map(|d| d.trim()).filter(|d| !d.is_empty()) {
+            match directive.split_once('=') {
+                Some((target, level)) => {
+                    let level = match parse_level(level) {
+                        Some(level) => Some(level),
+                        None if level.trim().eq_ignore_ascii_case("off") => None,
+                        // Unknown levels are ignored like RUST_LOG does
+                        None => continue,
+                    };
+                    filter.directives.push((target.trim().to_string(), level));
+                }
+                None => match parse_level(directive) {
+                    Some(level) => filter.default = Some(level),
+                    None if directive.eq_ignore_ascii_case("off") => filter.default = None,
+                    // A bare target enables everything for it
+                    None => filter
+                        .directives
+                        .push((directive.to_string(), Some(Level::Trace))),
+                },
+            }
+        }
+        filter.directives.sort_by_key(|(target, _)| target.len());
+        filter
+    }
+
+    pub fn from_env(var: &str) -> Option<Self> {
+        std::env::var(var)
+            .ok()
+            .map(|spec| TargetFilter::parse(&spec))
+    }
+
+    pub fn directive(mut self, target: &str, level: Option<Level>) -> Self {
+        self.directives.push((target.to_string(), level));
+        self.directives.sort_by_key(|(target, _)| target.len());
+        self
+    }
+
+    pub fn enabled(&self, level: Level, target: &str) -> bool {
+        let min = self
+            .directives
+            .iter()
+            .rev()
+            .find(|(t, _)| {
+                target == t
+                    || (target.starts_with(t.as_str()) && target[t.len()..].starts_with("::"))
+            })
+            .map_or(self.default, |(_, level)| *level);
+        min.is_some_and(|min| level >= min)
+    }
+
+    // Most verbose level any directive lets through.
+    pub fn max_verbosity(&self) -> Option<Level> {
+        self.directives
+            .iter()
+            .filter_map(|(_, level)| *level)
+            .chain(self.default)
+            .min()
+    }
+}
+
+fn parse_level(level: &str) -> Option<Level> {
+    level.parse().ok()
+}
+
+#[derive(Clone, Debug)]
+pub struct LogConfig {
+    filter: TargetFilter,
+    timestamp: Option<OwnedFormatItem>,
+    show_target: bool,
+    target_width: usize,
+}
+
+impl Default for LogConfig {
+    // Filter from TERMPRINT_LOG, everything from Info up if unset.
+    fn default() -> Self {
+        LogConfig {
+            filter: TargetFilter::from_env(LOG_LEVEL_ENV)
+                .unwrap_or_else(|| TargetFilter::new(DEFAULT_LEVEL)),
+            timestamp: format_description::parse_owned::<1>(TIMESTAMP_FORMAT).ok(),
+            show_target: true,
+            target_width: 0,
+        }
+    }
+}
+
+impl LogConfig {
+    pub fn min_level(mut self, level: Level) -> Self {
+        self.filter = TargetFilter::new(level);
+        self
+    }
+
+    pub fn filter(mut self, filter: TargetFilter) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    // Format description of the `time` crate, e.g. "[year]-[month]-[day] [hour]:[minute]".
+    // The time is UTC.
+    pub fn timestamp(mut self, format: &str) -> Result<Self, String> {
+        self.timestamp =
+            Some(format_description::parse_owned::<1>(format).map_err(|e| e.to_string())?);
+        Ok(self)
+    }
+
+    pub fn no_timestamp(mut self) -> Self {
+        self.timestamp = None;
+        self
+    }
+
+    pub fn show_target(mut self, show: bool) -> Self {
+        self.show_target = show;
+        self
+    }
+
+    // Pads the target column so messages of different targets line up.
+    pub fn target_width(mut self, width: usize) -> Self {
+        self.target_width = width;
+        self
+    }
+
+    pub fn enabled(&self, level: Level, target: Option<&str>) -> bool {
+        self.filter.enabled(level, target.unwrap_or(""))
+    }
+}
+
+pub fn set_log_config(config: LogConfig) {
+    if let Ok(mut c) = LOG_CONFIG.write() {
+        *c = Some(config);
+    }
+}
+
+pub fn log_config() -> LogConfig {
+    if let Some(config) = LOG_CONFIG.read().ok().and_then(|c| c.clone()) {
+        return config;
+    }
+    let config = LogConfig::default();
+    set_log_config(config.clone());
+    config
+}
+
+pub fn str_log(config: &LogConfig, level: Level, target: Option<&str>, msg: &str) -> String {
+    let mut output = String::new();
+    if let Some(format) = &config.timestamp {
+        let now = OffsetDateTime::now_utc();
+        if let Ok(ts) = now.format(format) {
+            output.push_str(&format!("{} ", ts.as_str().cline()));
+        }
+    }
+    output.push_str(&format!("{} ", level.badge()));
+    if let Some(t) = target.filter(|_| config.show_target) {
+        let target = format!("{:<w$}", format!("[{}]", t), w = config.target_width);
+        output.push_str(&format!("{} ", target.as_str().ctitle()));
+    }
+    let msg = match level {
+        Level::Error => msg.cerror(),
+        Level::Warn => msg.cwarning(),
+        _ => msg.cvar(),
+    };
+    output.push_str(&msg.to_string());
+    output
+}
+
+// Warnings and errors go to stderr, everything else to stdout.
+pub fn log(level: Level, target: Option<&str>, msg: &str) {
+    let config = log_config();
+    if !config.enabled(level, target) {
+        return;
+    }
+    let _ = if level >= Level::Warn {
+        Printer::stderr().log(&config, level, target, msg)
+    } else {
+        Printer::stdout().log(&config, level, target, msg)
+    };
+}
+
+pub fn log_trace(msg: &str) {
+    log(Level::Trace, None, msg);
+}
+
+pub fn log_debug(msg: &str) {
+    log(Level::Debug, None, msg);
+}
+
+pub fn log_info(msg: &str) {
+    log(Level::Info, None, msg);
+}
+
+pub fn log_warn(msg: &str) {
+    log(Level::Warn, None, msg);
+}
+
+pub fn log_error(msg: &str) {
+    log(Level::Error, None, msg);
+}
+
+// Logs with the calling module as target, e.g. `tp_log!(Level::Info, "{} rows", n)`.
+#[macro_export]
+macro_rules! tp_log {
+    ($level:expr, $($arg:tt)+) => {
+        $crate::log($level, Some(module_path!()), &format!($($arg)+))
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::printer::strip_ansi;
+
+    #[test]
+    fn bare_level_sets_the_default() {
+        let filter = TargetFilter::parse("warn");
+        assert!(filter.enabled(Level::Warn, "any::target"));
+        assert!(!filter.enabled(Level::Info, "any::target"));
+        assert_eq!(filter.max_verbosity(), Some(Level::Warn));
+    }
+
+    #[test]
+    fn unmatched_targets_use_the_default_level() {
+        let filter = TargetFilter::parse("mycrate=debug");
+        assert!(filter.enabled(Level::Debug, "mycrate"));
+        assert!(filter.enabled(Level::Debug, "mycrate::net"));
+        assert!(!filter.enabled(Level::Debug, "mycrate_other"));
+        assert!(filter.enabled(Level::Info, "hyper::client"));
+        assert!(!filter.enabled(Level::Debug, "hyper::client"));
+        assert!(filter.enabled(Level::Info, ""));
+    }
+
+    #[test]
+    fn longest_target_wins() {
+        let filter = TargetFilter::parse(" error , app=info,app::db=trace, app::db::pool=off ");
+        assert!(!filter.enabled(Level::Warn, "other"));
+        assert!(filter.enabled(Level::Info, "app::http"));
+        assert!(!filter.enabled(Level::Debug, "app::http"));
+        assert!(filter.enabled(Level::Trace, "app::db::query"));
+        assert!(!filter.enabled(Level::Error, "app::db::pool"));
+        assert_eq!(filter.max_verbosity(), Some(Level::Trace));
+    }
+
+    #[test]
+    fn off_and_bare_targets() {
+        let filter = TargetFilter::parse("off,app");
+        assert!(!filter.enabled(Level::Error, "other"));
+        assert!(filter.enabled(Level::Trace, "app::net"));
+
+        let filter = TargetFilter::parse("info,noisy=OFF");
+        assert!(!filter.enabled(Level::Error, "noisy"));
+        assert!(filter.enabled(Level::Info, "quiet"));
+    }
+
+    #[test]
+    fn invalid_directives_are_ignored() {
+        let filter = TargetFilter::parse("warn,noisy=nonsense,app=debug,=,bad=");
+        assert!(filter.enabled(Level::Warn, "noisy"));
+        assert!(!filter.enabled(Level::Info, "noisy"));
+        assert!(filter.enabled(Level::Debug, "app"));
+        assert_eq!(filter.max_verbosity(), Some(Level::Debug));
+        assert!(filter.enabled(Level::Warn, "bad"));
+    }
+
+    #[test]
+    fn timestamps_are_marked_utc() {
+        let config = LogConfig::default().filter(TargetFilter::new(Level::Trace));
+        let line = strip_ansi(&str_log(&config, Level::Info, None, "hello"));
+        let (ts, rest) = line.split_once(' ').unwrap();
+        assert!(ts.ends_with('Z'), "{}", line);
+        assert_eq!(ts.len(), "12:34:56.789Z".len(), "{}", line);
+        assert!(rest.contains("hello"), "{}", line);
+    }
+}
@@ -1,14 +1,9 @@
-mod termprint;
 use std::{collections::HashMap, vec};
 
 use serde::Serialize;
 
 use termprint as tp;
 
-mod request_ext;
-
-use request_ext::{request_to_hashmap, response_to_hashmap, HttpMethod};
-
 fn main() {
     let start = tp::print_start_program("Test termprint");
 
@@ -99,6 +94,7 @@ fn main() {
     let mut map = HashMap::new();
     map.insert("key1", "value1");
     map.insert("key23", "value2");
+    map.insert("password", "hunter2");
     map.insert("many key3", "valuevaluevaluevaluevaluevaluevaluevaluevaluevaluevaluevaluevaluevaluevaluevaluevaluevaluevaluevaluevaluevaluevaluevaluevaluevaluevaluevaluevaluevalue");
     tp::print_hashmap(&map, Some("Map"));
 
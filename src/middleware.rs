@@ -0,0 +1,181 @@
+use std::fmt;
+use std::io::{self, Write};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use async_trait::async_trait;
+use http::Extensions;
+use reqwest::{Request, Response, ResponseBuilderExt};
+use reqwest_middleware::{Error, Middleware, Next, Result};
+
+use crate::exchange::{str_exchange_summary, BodyLimits, HttpExchange};
+use crate::export::HarRecorder;
+use crate::printer::Printer;
+use crate::redact::redact_policy;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Verbosity {
+    Status,
+    Headers,
+    Bodies,
+}
+
+// Prints every request sent through a reqwest_middleware::ClientWithMiddleware,
+// to stdout or the Printer given with `printer`. With Verbosity::Bodies or a
+// HAR recorder attached the response body is read into memory and handed on
+// as a rebuilt Response with the same status, headers, url() and extensions.
+pub struct PrintMiddleware<W: Write = io::Stdout> {
+    verbosity: Verbosity,
+    limits: BodyLimits,
+    recorder: Option<Arc<HarRecorder>>,
+    printer: Arc<Mutex<Printer<W>>>,
+}
+
+impl Default for PrintMiddleware {
+    fn default() -> Self {
+        PrintMiddleware::new(Verbosity::Headers)
+    }
+}
+
+impl<W: Write> Clone for PrintMiddleware<W> {
+    fn clone(&self) -> Self {
+        PrintMiddleware {
+            verbosity: self.verbosity,
+            limits: self.limits,
+            recorder: self.recorder.clone(),
+            printer: self.printer.clone(),
+        }
+    }
+}
+
+impl<W: Write> fmt::Debug for PrintMiddleware<W> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("PrintMiddleware")
+            .field("verbosity", &self.verbosity)
+            .field("limits", &self.limits)
+            .field("recorder", &self.recorder)
+            .finish_non_exhaustive()
+    }
+}
+
+impl PrintMiddleware {
+    pub fn new(verbosity: Verbosity) -> Self {
+        PrintMiddleware {
+            verbosity,
+            limits: BodyLimits::default(),
+            recorder: None,
+            printer: Arc::new(Mutex::new(Printer::stdout())),
+        }
+    }
+}
+
+impl<W: Write> PrintMiddleware<W> {
+    // Output goes to this printer instead of stdout, e.g. a shared
+    // Printer::new(Vec::new()) to capture it
+    pub fn printer<V: Write>(self, printer: Arc<Mutex<Printer<V>>>) -> PrintMiddleware<V> {
+        PrintMiddleware {
+            verbosity: self.verbosity,
+            limits: self.limits,
+            recorder: self.recorder,
+            printer,
+        }
+    }
+
+    pub fn limits(mut self, limits: BodyLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    pub fn recorder(mut self, recorder: Arc<HarRecorder>) -> Self {
+        self.recorder = Some(recorder);
+        self
+    }
+
+    fn print(&self, exchange: &HttpExchange) {
+        let Ok(mut printer) = self.printer.lock() else {
+            return;
+        };
+        let _ = match self.verbosity {
+            Verbosity::Status => {
+                let summary = printer.render(|| format!("{}\n", str_exchange_summary(exchange)));
+                printer.write_str(&summary)
+            }
+            Verbosity::Headers => {
+                let mut headers_only = exchange.clone();
+                headers_only.request_body = None;
+                headers_only.response_body = None;
+                printer.exchange(&headers_only, None)
+            }
+            Verbosity::Bodies => printer.exchange(exchange, Some(self.limits)),
+        };
+    }
+
+    fn print_error(&self, target: &str, error: &str) {
+        if let Ok(mut printer) = self.printer.lock() {
+            let _ = printer.error("Request failed", Some(target), Some(error));
+        }
+    }
+}
+
+#[async_trait]
+impl<W: Write + Send + 'static> Middleware for PrintMiddleware<W> {
+    async fn handle(
+        &self,
+        req: Request,
+        extensions: &mut Extensions,
+        next: Next<'_>,
+    ) -> Result<Response> {
+        let exchange = HttpExchange::from_request(&req);
+        let start = Instant::now();
+        let mut response = match next.run(req, extensions).await {
+            Ok(response) => response,
+            Err(e) => {
+                let target = format!(
+                    "{} {}",
+                    exchange.method,
+                    redact_policy().redact_url(&exchange.url)
+                );
+                self.print_error(&target, &e.to_string());
+                if let Some(recorder) = &self.recorder {
+                    recorder.record(&exchange.with_elapsed(start.elapsed()));
+                }
+                return Err(e);
+            }
+        };
+
+        let status = response.status();
+        let version = response.version();
+        let headers = response.headers().clone();
+        if self.verbosity < Verbosity::Bodies && self.recorder.is_none() {
+            let exchange = exchange
+                .with_response(status, &format!("{:?}", version), &headers, &[])
+                .with_elapsed(start.elapsed());
+            self.print(&exchange);
+            return Ok(response);
+        }
+
+        let url = response.url().clone();
+        let extensions = std::mem::take(response.extensions_mut());
+        let body = response.bytes().await?;
+        let exchange = exchange
+            .with_response(status, &format!("{:?}", version), &headers, &body)
+            .with_elapsed(start.elapsed());
+        self.print(&exchange);
+        if let Some(recorder) = &self.recorder {
+            recorder.record(&exchange);
+        }
+
+        let mut builder = http::Response::builder()
+            .status(status)
+            .version(version)
+            .url(url);
+        if let Some(h) = builder.headers_mut() {
+            *h = headers;
+        }
+        if let Some(e) = builder.extensions_mut() {
+            e.extend(extensions);
+        }
+        let rebuilt = builder.body(body).map_err(Error::middleware)?;
+        Ok(Response::from(rebuilt))
+    }
+}
@@ -0,0 +1,354 @@
+use std::io;
+
+use crate::printer::{display_width, Printer};
+use crate::termprint::{get_terminal_width, ColoredItem, RESET};
+use crate::theme::{with_theme, Style};
+
+const TAB: &str = "    ";
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Border {
+    #[default]
+    Rounded,
+    Single,
+    Double,
+    Heavy,
+    Ascii,
+}
+
+// Corners top left, top right, bottom left, bottom right, then horizontal and vertical
+impl Border {
+    fn chars(&self) -> [&'static str; 6] {
+        match self {
+            Border::Rounded => ["╭", "╮", "╰", "╯", "─", "│"],
+            Border::Single => ["┌", "┐", "└", "┘", "─", "│"],
+            Border::Double => ["╔", "╗", "╚", "╝", "═", "║"],
+            Border::Heavy => ["┏", "┓", "┗", "┛", "━", "┃"],
+            Border::Ascii => ["+", "+", "+", "+", "-", "|"],
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Align {
+    #[default]
+    Left,
+    Center,
+    Right,
+}
+
+// Framed box around text, e.g. notices, summaries or the output of other
+// widgets. Lines that fit are kept as they are, so aligned output such as
+// str_hashmap stays aligned; longer lines are word-wrapped. Widgets that size
+// themselves to the terminal width should be rendered with content_with so
+// they fit the panel instead of being wrapped.
+#[derive(Clone, Debug)]
+pub struct Panel {
+    content: String,
+    title: Option<String>,
+    border: Border,
+    style: Option<Style>,
+    width: Option<usize>,
+    padding: (usize, usize),
+    align: Align,
+}
+
+impl Panel {
+    pub fn new(content: &str) -> Self {
+        Panel {
+            content: content.to_string(),
+            title: None,
+            border: Border::default(),
+            style: None,
+            width: None,
+            padding: (1, 0),
+            align: Align::default(),
+        }
+    }
+
+    pub fn title(mut self, title: &str) -> Self {
+        self.title = Some(title.to_string());
+        self
+    }
+
+    pub fn border(mut self, border: Border) -> Self {
+        self.border = border;
+        self
+    }
+
+    // Color of border and title instead of the theme's line and title roles,
+    // e.g. theme().error for an error box.
+    pub fn style(mut self, style: Style) -> Self {
+        self.style = Some(style);
+        self
+    }
+
+    // Total width including the border. Without it the panel fits its
+    // content, up to get_terminal_width().
+    pub fn width(mut self, width: usize) -> Self {
+        self.width = Some(width);
+        self
+    }
+
+    // Spaces left and right of the content, empty lines above and below
+    pub fn padding(mut self, horizontal: usize, vertical: usize) -> Self {
+        self.padding = (horizontal, vertical);
+        self
+    }
+
+    pub fn align(mut self, align: Align) -> Self {
+        self.align = align;
+        self
+    }
+
+    // Renders the content with the terminal width scoped to the panel's
+    // content area, e.g. `.content_with(|| str_hashmap(&map, None))`. Set
+    // width and padding first.
+    pub fn content_with<F: FnOnce() -> String>(mut self, f: F) -> Self {
+        let inner = self.max_inner_width();
+        self.content = Printer::new(io::sink()).width(inner).render(f);
+        self
+    }
+
+    // Widest content that fits without wrapping
+    fn max_inner_width(&self) -> usize {
+        let width = self.width.unwrap_or_else(get_terminal_width);
+        width.saturating_sub(2 + 2 * self.padding.0).max(1)
+    }
+
+    fn paint_border(&self, s: &str) -> String {
+        match self.style {
+            Some(style) => with_theme(|t| t.paint(style, s)).to_string(),
+            None => s.cline().to_string(),
+        }
+    }
+
+    fn paint_title(&self, s: &str) -> String {
+        match self.style {
+            Some(style) => with_theme(|t| t.paint(style.bold(), s)).to_string(),
+            None => s.ctitle().to_string(),
+        }
+    }
+
+    pub fn render(&self) -> String {
+        let [tl, tr, bl, br, h, v] = self.border.chars();
+        let (pad_x, pad_y) = self.padding;
+        let content = self.content.replace('\t', TAB);
+        let lines: Vec<&str> = content.trim_end_matches('\n').lines().collect();
+        let title: Option<String> = self.title.as_ref().map(|t| t.replace('\n', " "));
+        let title_w = title.as_ref().map_or(0, |t| t.chars().count() + 4);
+
+        let width = self.width.unwrap_or_else(|| {
+            let content_w = lines.iter().map(|l| display_width(l)).max().unwrap_or(0);
+            (content_w + 2 + 2 * pad_x)
+                .max(title_w + 2)
+                .min(get_terminal_width())
+        });
+        let inner = width.saturating_sub(2 + 2 * pad_x).max(1);
+        let width = inner + 2 + 2 * pad_x;
+
+        let mut output = match &title {
+            Some(title) if width >= 6 => {
+                let title: String = title.chars().take(width - 6).collect();
+                let rest = width - 4 - title.chars().count();
+                format!(
+                    "{}{}{}\n",
+                    self.paint_border(&format!("{}{} ", tl, h)),
+                    self.paint_title(&title),
+                    self.paint_border(&format!(" {}{}", h.repeat(rest - 1), tr))
+                )
+            }
+            _ => format!(
+                "{}\n",
+                self.paint_border(&format!("{}{}{}", tl, h.repeat(width - 2), tr))
+            ),
+        };
+        let side = self.paint_border(v);
+        let empty = format!("{}{}{}\n", side, " ".repeat(width - 2), side);
+        for _ in 0..pad_y {
+            output.push_str(&empty);
+        }
+        for line in lines {
+            let wrapped = if display_width(line) <= inner {
+                vec![line.to_string()]
+            } else {
+                wrap(line, inner)
+            };
+            for part in wrapped {
+                let gap = inner.saturating_sub(display_width(&part));
+                let left = match self.align {
+                    Align::Left => 0,
+                    Align::Center => gap / 2,
+                    Align::Right => gap,
+                };
+                output.push_str(&format!(
+                    "{}{}{}{}{}{}\n",
+                    side,
+                    " ".repeat(pad_x + left),
+                    part,
+                    " ".repeat(gap - left),
+                    " ".repeat(pad_x),
+                    side
+                ));
+            }
+        }
+        for _ in 0..pad_y {
+            output.push_str(&empty);
+        }
+        output.push_str(&format!(
+            "{}\n",
+            self.paint_border(&format!("{}{}{}", bl, h.repeat(width - 2), br))
+        ));
+        output
+    }
+
+    pub fn print(&self) {
+        let _ = Printer::stdout().panel(self);
+    }
+}
+
+// Splits text into ANSI escape sequences and visible characters.
+fn tokens(s: &str) -> Vec<(String, bool)> {
+    let mut tokens = Vec::new();
+    let mut chars = s.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' && chars.peek() == Some(&'[') {
+            let mut seq = String::from(c);
+            for c in chars.by_ref() {
+                seq.push(c);
+                if ('@'..='~').contains(&c) && c != '[' {
+                    break;
+                }
+            }
+            tokens.push((seq, false));
+        } else {
+            tokens.push((c.to_string(), true));
+        }
+    }
+    tokens
+}
+
+// Word-wraps a line to `width` visible characters. Words longer than a line
+// are split; colors active at a break are closed and reopened on the next line.
+pub fn wrap(text: &str, width: usize) -> Vec<String> {
+    let width = width.max(1);
+    let mut lines = Vec::new();
+    for source in text.lines() {
+        let mut line = String::new();
+        let mut line_w = 0;
+        // SGR sequences in effect since the last reset
+        let mut active = String::new();
+        let mut break_line = |line: &mut String, line_w: &mut usize, active: &str| {
+            if !active.is_empty() {
+                line.push_str(RESET);
+            }
+            lines.push(std::mem::take(line).trim_end().to_string());
+            line.push_str(active);
+            *line_w = 0;
+        };
+        for (i, word) in source.split(' ').enumerate() {
+            let word_w = display_width(word);
+            if i > 0 {
+                if line_w > 0 && line_w + 1 + word_w > width {
+                    break_line(&mut line, &mut line_w, &active);
+                } else {
+                    line.push(' ');
+                    line_w += 1;
+                }
+            }
+            for (token, visible) in tokens(word) {
+                if visible {
+                    if line_w >= width {
+                        break_line(&mut line, &mut line_w, &active);
+                    }
+                    line_w += 1;
+                } else if token == RESET || token == "\x1b[m" {
+                    active.clear();
+                } else if token.ends_with('m') {
+                    active.push_str(&token);
+                }
+                line.push_str(&token);
+            }
+        }
+        lines.push(line.trim_end().to_string());
+    }
+    lines
+}
+
+pub fn str_panel(title: &str, content: &str) -> String {
+    Panel::new(content).title(title).render()
+}
+
+pub fn print_panel(title: &str, content: &str) {
+    Panel::new(content).title(title).print();
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::printer::strip_ansi;
+    use crate::termprint::str_hashmap;
+
+    const RED: &str = "\x1b[31m";
+
+    #[test]
+    fn wrap_breaks_at_spaces() {
+        assert_eq!(
+            wrap("the quick brown fox jumps", 10),
+            vec!["the quick", "brown fox", "jumps"]
+        );
+        assert_eq!(wrap("fits", 10), vec!["fits"]);
+        assert_eq!(wrap("one\ntwo", 10), vec!["one", "two"]);
+        assert_eq!(wrap("", 10), Vec::<String>::new());
+    }
+
+    #[test]
+    fn wrap_splits_long_words() {
+        assert_eq!(wrap("abcdefghij", 4), vec!["abcd", "efgh", "ij"]);
+        assert_eq!(wrap("ab abcdefgh", 4), vec!["ab", "abcd", "efgh"]);
+        assert_eq!(
+            wrap("héllo wörld", 0),
+            "héllowörld".chars().map(String::from).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn wrap_reopens_colors() {
+        let text = format!("{}red text here{} plain", RED, RESET);
+        let lines = wrap(&text, 8);
+        assert_eq!(
+            lines,
+            vec![
+                format!("{}red text{}", RED, RESET),
+                format!("{}here{}", RED, RESET),
+                "plain".to_string(),
+            ]
+        );
+        assert!(lines.iter().all(|l| display_width(l) <= 8));
+    }
+
+    #[test]
+    fn content_with_fits_the_panel() {
+        let map = HashMap::from([("description", "word ".repeat(40))]);
+        let panel = Panel::new("")
+            .width(40)
+            .content_with(|| str_hashmap(&map, None))
+            .render();
+        let lines: Vec<String> = panel.lines().map(strip_ansi).collect();
+        assert!(lines.iter().all(|l| display_width(l) == 40), "{}", panel);
+
+        // Same lines as the hashmap rendered at the inner width, none wrapped
+        let inner = Printer::new(io::sink())
+            .width(36)
+            .render(|| str_hashmap(&map, None));
+        assert_eq!(lines.len(), inner.lines().count() + 2, "{}", panel);
+        for (line, expected) in lines[1..].iter().zip(inner.lines()) {
+            assert_eq!(
+                line[4..].trim_end_matches(['│', ' ']),
+                strip_ansi(expected).trim_end()
+            );
+        }
+    }
+}
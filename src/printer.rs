@@ -0,0 +1,556 @@
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt::Display;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use time::OffsetDateTime;
+
+use crate::exchange::{str_exchange, BodyLimits, HttpExchange};
+use crate::export::request_to_curl;
+use crate::graph::Chart;
+use crate::layout::Columns;
+use crate::logger::{str_log, Level, LogConfig};
+use crate::panel::Panel;
+use crate::report::{str_error_report, ErrorReport};
+use crate::request_ext::{HttpMethod, RequestParts, ResponseParts};
+use crate::termprint::{
+    double_line, error, info, line, make_header, make_table, message, str_end_program, str_hashmap,
+    str_headers, str_map, str_request, str_response, str_start_program, str_struct,
+    str_terminal_type, str_title, str_tree_item, str_vec, str_vec_struct, warning, ColoredItem,
+    TreeBlock,
+};
+use crate::theme::{swap_scoped_theme, Theme};
+
+const MIN_WIDTH: usize = 10;
+
+thread_local! {
+    // Set while a Printer with a fixed width renders a widget
+    static SCOPED_WIDTH: Cell<Option<usize>> = const { Cell::new(None) };
+    static THREAD_PREFIX: RefCell<Option<String>> = const { RefCell::new(None) };
+    // Set while a Printer with ColorPolicy::Never renders a widget
+    static SCOPED_PLAIN: Cell<bool> = const { Cell::new(false) };
+}
+
+pub(crate) fn scoped_width() -> Option<usize> {
+    SCOPED_WIDTH.with(|w| w.get())
+}
+
+// Whether rendered colors will reach the output. Widgets that encode values
+// only in color (e.g. heatmaps) fall back to glyphs otherwise.
+pub fn colors_enabled() -> bool {
+    !SCOPED_PLAIN.with(|p| p.get()) && colored::control::SHOULD_COLORIZE.should_colorize()
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ColorPolicy {
+    // Colors as the colored crate decides (NO_COLOR, CLICOLOR_FORCE, tty)
+    #[default]
+    Auto,
+    // Plain text, escape sequences are stripped
+    Never,
+}
+
+// Name put in front of the line-oriented messages (info, message, warning,
+// error, log lines) of the current thread, e.g. the worker name.
+pub fn set_thread_prefix(name: &str) {
+    THREAD_PREFIX.with(|p| *p.borrow_mut() = Some(name.to_string()));
+}
+
+pub fn clear_thread_prefix() {
+    THREAD_PREFIX.with(|p| *p.borrow_mut() = None);
+}
+
+pub fn thread_prefix() -> Option<String> {
+    THREAD_PREFIX.with(|p| p.borrow().clone())
+}
+
+// "[name] " in a column color derived from the name, so it stays the same
+// for a worker across lines and runs.
+pub fn str_prefix(name: &str) -> String {
+    let hash = name.bytes().fold(0x811c9dc5_u32, |h, b| {
+        (h ^ b as u32).wrapping_mul(0x01000193)
+    });
+    format!("{} ", format!("[{}]", name).column(hash as usize))
+}
+
+pub(crate) fn prefix_lines(prefix: Option<&str>, text: &str) -> String {
+    match prefix {
+        None => text.to_string(),
+        Some(name) => {
+            let prefix = str_prefix(name);
+            text.split_inclusive('\n')
+                .map(|l| format!("{}{}", prefix, l))
+                .collect()
+        }
+    }
+}
+
+// Restores the previous theme and width when rendering ends, even on panic.
+struct Scope {
+    theme: Option<Option<Theme>>,
+    width: Option<usize>,
+    plain: bool,
+}
+
+impl Drop for Scope {
+    fn drop(&mut self) {
+        if let Some(theme) = self.theme.take() {
+            swap_scoped_theme(theme);
+        }
+        SCOPED_WIDTH.with(|w| w.set(self.width));
+        SCOPED_PLAIN.with(|p| p.set(self.plain));
+    }
+}
+
+// Renders the widgets into any io::Write. Theme and width default to the
+// global theme and the terminal width, e.g.
+// `Printer::new(Vec::new()).width(80).color(ColorPolicy::Never)` to capture output.
+// Each widget is rendered completely and written with a single write_all, so
+// concurrent widgets on stdout (which locks per call) never interleave.
+pub struct Printer<W: Write> {
+    out: W,
+    theme: Option<Theme>,
+    width: Option<usize>,
+    color: ColorPolicy,
+    prefix: Option<String>,
+}
+
+impl Printer<io::Stdout> {
+    pub fn stdout() -> Self {
+        Printer::new(io::stdout())
+    }
+}
+
+impl Printer<io::Stderr> {
+    pub fn stderr() -> Self {
+        Printer::new(io::stderr())
+    }
+}
+
+impl Printer<BufWriter<File>> {
+    // Files get plain text
+    pub fn file<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Ok(Printer::new(BufWriter::new(File::create(path)?)).color(ColorPolicy::Never))
+    }
+}
+
+impl<W: Write> Printer<W> {
+    pub fn new(out: W) -> Self {
+        Printer {
+            out,
+            theme: None,
+            width: None,
+            color: ColorPolicy::Auto,
+            prefix: None,
+        }
+    }
+
+    pub fn theme(mut self, theme: Theme) -> Self {
+        self.theme = Some(theme);
+        self
+    }
+
+    // At least MIN_WIDTH, so widgets always have room for a column
+    pub fn width(mut self, width: usize) -> Self {
+        self.width = Some(width.max(MIN_WIDTH));
+        self
+    }
+
+    pub fn color(mut self, color: ColorPolicy) -> Self {
+        self.color = color;
+        self
+    }
+
+    // Overrides the thread prefix, e.g. for async tasks that move between threads
+    pub fn prefix(mut self, name: &str) -> Self {
+        self.prefix = Some(name.to_string());
+        self
+    }
+
+    pub fn get_ref(&self) -> &W {
+        &self.out
+    }
+
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.out
+    }
+
+    pub fn into_inner(self) -> W {
+        self.out
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.out.flush()
+    }
+
+    // Runs a str_* builder with this printer's theme and width.
+    pub fn render<F: FnOnce() -> String>(&self, f: F) -> String {
+        let _scope = Scope {
+            theme: self.theme.clone().map(|t| swap_scoped_theme(Some(t))),
+            width: SCOPED_WIDTH.with(|w| w.replace(self.width.or(w.get()))),
+            plain: SCOPED_PLAIN.with(|p| p.replace(p.get() || self.color == ColorPolicy::Never)),
+        };
+        let output = f();
+        match self.color {
+            ColorPolicy::Auto => output,
+            ColorPolicy::Never => strip_ansi(&output),
+        }
+    }
+
+    // Writes already rendered text, stripped according to the color policy.
+    pub fn write_str(&mut self, s: &str) -> io::Result<()> {
+        let output = match self.color {
+            ColorPolicy::Auto => s.to_string(),
+            ColorPolicy::Never => strip_ansi(s),
+        };
+        self.out.write_all(output.as_bytes())
+    }
+
+    fn emit<F: FnOnce() -> String>(&mut self, f: F) -> io::Result<()> {
+        let output = self.render(f);
+        self.out.write_all(output.as_bytes())
+    }
+
+    fn emitln<F: FnOnce() -> String>(&mut self, f: F) -> io::Result<()> {
+        self.emit(|| format!("{}\n", f()))
+    }
+
+    // Line-oriented messages carry the printer or thread prefix.
+    fn emit_prefixed<F: FnOnce() -> String>(&mut self, f: F) -> io::Result<()> {
+        let prefix = self.prefix.clone().or_else(thread_prefix);
+        self.emit(|| prefix_lines(prefix.as_deref(), &format!("{}\n", f())))
+    }
+
+    pub fn title(&mut self, msg: &str) -> io::Result<()> {
+        self.emit(|| str_title(msg))
+    }
+
+    pub fn message(&mut self, txt: &str) -> io::Result<()> {
+        self.emit_prefixed(|| message(txt))
+    }
+
+    pub fn info(&mut self, key: &str, value: &str) -> io::Result<()> {
+        self.emit_prefixed(|| info(key, value))
+    }
+
+    pub fn warning(&mut self, msg: &str) -> io::Result<()> {
+        self.emit_prefixed(|| warning(msg))
+    }
+
+    pub fn error(
+        &mut self,
+        message: &str,
+        info: Option<&str>,
+        err_msg: Option<&str>,
+    ) -> io::Result<()> {
+        self.emit_prefixed(|| error(message, info, err_msg))
+    }
+
+    pub fn log(
+        &mut self,
+        config: &LogConfig,
+        level: Level,
+        target: Option<&str>,
+        msg: &str,
+    ) -> io::Result<()> {
+        self.emit_prefixed(|| str_log(config, level, target, msg))
+    }
+
+    pub fn line(&mut self, length: usize) -> io::Result<()> {
+        self.emit(|| line(length))
+    }
+
+    pub fn double_line(&mut self, length: usize) -> io::Result<()> {
+        self.emit(|| double_line(length))
+    }
+
+    pub fn header(
+        &mut self,
+        headers: &[&str],
+        widths: &[usize],
+        start_index: usize,
+        last_index: usize,
+    ) -> io::Result<()> {
+        self.emitln(|| make_header(headers, widths, start_index, last_index))
+    }
+
+    pub fn map<T: Display>(&mut self, title: &str, val: &str, map: T) -> io::Result<()> {
+        self.emit(|| str_map(title, val, map))
+    }
+
+    pub fn vec(&mut self, vec: &Vec<&str>, title: Option<&str>) -> io::Result<()> {
+        self.emitln(|| format!("\n{}", str_vec(vec, title)))
+    }
+
+    pub fn hashmap<K: Display, V: Display>(
+        &mut self,
+        map: &HashMap<K, V>,
+        title: Option<&str>,
+    ) -> io::Result<()> {
+        self.emit(|| str_hashmap(map, title))
+    }
+
+    pub fn structure<T: serde::Serialize>(&mut self, title: &str, obj: &T) -> io::Result<()> {
+        self.emitln(|| str_struct(title, obj))
+    }
+
+    pub fn vec_struct<T: serde::Serialize>(&mut self, title: &str, vec: &[T]) -> io::Result<()> {
+        self.emit(|| str_vec_struct(title, vec))
+    }
+
+    pub fn table(
+        &mut self,
+        data: Vec<Vec<&str>>,
+        has_header: bool,
+        title: Option<&str>,
+        column_width: Option<usize>,
+    ) -> io::Result<()> {
+        self.emitln(|| make_table(data, has_header, title, column_width))
+    }
+
+    pub fn tree_item(&mut self, item: &str, block_type: TreeBlock) -> io::Result<()> {
+        self.emitln(|| str_tree_item(item, block_type))
+    }
+
+    pub fn terminal_type(&mut self) -> io::Result<()> {
+        self.emit(str_terminal_type)
+    }
+
+    pub fn start_program(&mut self, program_name: &str) -> io::Result<OffsetDateTime> {
+        let start = OffsetDateTime::now_utc();
+        self.emit(|| str_start_program(program_name, start))?;
+        Ok(start)
+    }
+
+    pub fn end_program(
+        &mut self,
+        program_name: &str,
+        start: OffsetDateTime,
+    ) -> io::Result<OffsetDateTime> {
+        let end = OffsetDateTime::now_utc();
+        self.emit(|| str_end_program(program_name, start, end))?;
+        Ok(end)
+    }
+
+    pub fn request<R: RequestParts>(&mut self, request: &R) -> io::Result<()> {
+        self.emit(|| str_request(request))
+    }
+
+    pub fn response<M: Into<HttpMethod>, R: ResponseParts>(
+        &mut self,
+        method: M,
+        response: &R,
+    ) -> io::Result<()> {
+        self.emit(|| str_response(method, response))
+    }
+
+    pub fn headers(&mut self, headers: &http::HeaderMap) -> io::Result<()> {
+        self.emit(|| str_headers(headers))
+    }
+
+    pub fn exchange(
+        &mut self,
+        exchange: &HttpExchange,
+        limits: Option<BodyLimits>,
+    ) -> io::Result<()> {
+        self.emitln(|| str_exchange(exchange, limits))
+    }
+
+    pub fn curl<R: RequestParts>(&mut self, request: &R) -> io::Result<()> {
+        self.emitln(|| request_to_curl(request, None))
+    }
+
+    pub fn error_report(&mut self, error: &dyn Error) -> io::Result<()> {
+        self.emit(|| str_error_report(error))
+    }
+
+    // An ErrorReport with snippets or backtrace settings
+    pub fn report(&mut self, report: &ErrorReport) -> io::Result<()> {
+        self.emit(|| report.render())
+    }
+
+    pub fn chart<C: Chart>(&mut self, chart: &C) -> io::Result<()> {
+        self.emit(|| chart.render())
+    }
+
+    pub fn panel(&mut self, panel: &Panel) -> io::Result<()> {
+        self.emit(|| panel.render())
+    }
+
+    pub fn columns(&mut self, columns: &Columns) -> io::Result<()> {
+        self.emit(|| columns.render())
+    }
+}
+
+// Removes ANSI escape sequences (CSI and OSC) from rendered output.
+pub fn strip_ansi(s: &str) -> String {
+    let mut output = String::with_capacity(s.len());
+    let mut chars = s.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\x1b' {
+            output.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('[') => {
+                for c in chars.by_ref() {
+                    if ('@'..='~').contains(&c) {
+                        break;
+                    }
+                }
+            }
+            Some(']') => {
+                while let Some(c) = chars.next() {
+                    if c == '\x07' {
+                        break;
+                    }
+                    if c == '\x1b' && chars.peek() == Some(&'\\') {
+                        chars.next();
+                        break;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    output
+}
+
+// Columns a rendered string takes: characters without escape sequences,
+// East Asian wide characters and emoji count twice.
+pub fn display_width(s: &str) -> usize {
+    strip_ansi(s).chars().map(char_width).sum()
+}
+
+// Terminal columns of a single character. Covers the common wide blocks
+// (CJK, Hangul, fullwidth forms, emoji); anything else takes one column.
+pub(crate) fn char_width(c: char) -> usize {
+    match c as u32 {
+        0x1100..=0x115F
+        | 0x2E80..=0x303E
+        | 0x3041..=0x33FF
+        | 0x3400..=0x4DBF
+        | 0x4E00..=0x9FFF
+        | 0xA000..=0xA4CF
+        | 0xAC00..=0xD7A3
+        | 0xF900..=0xFAFF
+        | 0xFE30..=0xFE4F
+        | 0xFF00..=0xFF60
+        | 0xFFE0..=0xFFE6
+        | 0x1F300..=0x1F64F
+        | 0x1F900..=0x1F9FF
+        | 0x20000..=0x3FFFD => 2,
+        _ => 1,
+    }
+}
+
+// Splits visible text into pieces of at most `width` columns. A character
+// wider than `width` still gets a piece of its own.
+pub(crate) fn split_width(s: &str, width: usize) -> Vec<String> {
+    let mut parts = vec![];
+    let mut part = String::new();
+    let mut part_w = 0;
+    for c in strip_ansi(s).chars() {
+        let w = char_width(c);
+        if part_w + w > width && !part.is_empty() {
+            parts.push(std::mem::take(&mut part));
+            part_w = 0;
+        }
+        part.push(c);
+        part_w += w;
+    }
+    if !part.is_empty() {
+        parts.push(part);
+    }
+    parts
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+    use std::thread;
+
+    use super::*;
+
+    // Like stdout: shared by all printers, locked for each write call.
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn threads_write_whole_prefixed_lines() {
+        let buffer = SharedBuffer::default();
+        let workers: Vec<_> = (0..4)
+            .map(|n| {
+                let buffer = buffer.clone();
+                thread::spawn(move || {
+                    let name = format!("worker-{}", n);
+                    set_thread_prefix(&name);
+                    let mut printer = Printer::new(buffer).color(ColorPolicy::Never);
+                    for i in 0..50 {
+                        printer
+                            .message(&format!("{} step {}\n{} done {}", name, i, name, i))
+                            .unwrap();
+                    }
+                })
+            })
+            .collect();
+        for worker in workers {
+            worker.join().unwrap();
+        }
+        assert_eq!(thread_prefix(), None);
+
+        let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 4 * 50 * 2);
+        for pair in lines.chunks(2) {
+            let (prefix, step) = pair[0].split_once(' ').unwrap();
+            let name = &prefix[1..prefix.len() - 1];
+            assert_eq!(prefix, format!("[{}]", name));
+            let i = step.rsplit(' ').next().unwrap();
+            assert_eq!(step, format!("{} step {}", name, i));
+            assert_eq!(pair[1], format!("[{}] {} done {}", name, name, i));
+        }
+    }
+
+    #[test]
+    fn printer_prefix_overrides_the_thread() {
+        set_thread_prefix("thread");
+        let mut printer = Printer::new(Vec::new())
+            .color(ColorPolicy::Never)
+            .prefix("job");
+        printer.message("a\nb").unwrap();
+        clear_thread_prefix();
+        let mut plain = Printer::new(Vec::new()).color(ColorPolicy::Never);
+        plain.message("c").unwrap();
+        assert_eq!(
+            String::from_utf8(printer.into_inner()).unwrap(),
+            "[job] a\n[job] b\n"
+        );
+        assert_eq!(String::from_utf8(plain.into_inner()).unwrap(), "c\n");
+    }
+
+    #[test]
+    fn wide_characters_take_two_columns() {
+        assert_eq!(display_width("abc"), 3);
+        assert_eq!(display_width("héllo"), 5);
+        assert_eq!(display_width("東京"), 4);
+        assert_eq!(display_width("\x1b[31m한글\x1b[0m"), 4);
+        assert_eq!(split_width("a東京b", 3), vec!["a東", "京b"]);
+        assert_eq!(split_width("東京", 1), vec!["東", "京"]);
+        assert!(split_width("", 4).is_empty());
+    }
+}
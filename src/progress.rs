@@ -0,0 +1,335 @@
+use std::io::{self, IsTerminal, Write};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::printer::{display_width, strip_ansi};
+use crate::termprint::{get_terminal_width, ColoredItem};
+
+const REDRAW_INTERVAL: Duration = Duration::from_millis(50);
+const PLAIN_INTERVAL: Duration = Duration::from_secs(5);
+const MIN_BAR_WIDTH: usize = 10;
+const UNKNOWN_ETA: &str = "ETA --:--";
+
+struct BarState {
+    label: String,
+    position: u64,
+    total: u64,
+    started: Instant,
+    finished: bool,
+    // Final line already written (plain output only)
+    reported: bool,
+}
+
+struct DrawState {
+    bars: Vec<Arc<Mutex<BarState>>>,
+    lines: usize,
+    last_draw: Option<Instant>,
+    tty: bool,
+}
+
+impl DrawState {
+    fn render(&mut self, tty: bool) -> Vec<String> {
+        let width = get_terminal_width().saturating_sub(1);
+        // All bars stay locked while rendering, so labels can't change
+        // between measuring and padding them
+        let mut bars: Vec<_> = self.bars.iter().filter_map(|bar| bar.lock().ok()).collect();
+        // Pad labels so the bars of all lines start in the same column
+        let label_width = bars
+            .iter()
+            .map(|bar| display_width(&bar.label))
+            .max()
+            .unwrap_or(0);
+        bars.iter_mut()
+            .filter_map(|bar| {
+                if !tty && bar.reported {
+                    return None;
+                }
+                bar.reported = bar.finished;
+                let label = format!(
+                    "{}{}",
+                    bar.label,
+                    " ".repeat(label_width.saturating_sub(display_width(&bar.label)))
+                );
+                Some(str_progress(
+                    &label,
+                    bar.position,
+                    bar.total,
+                    bar.started.elapsed(),
+                    bar.finished,
+                    width,
+                ))
+            })
+            .collect()
+    }
+
+    // On a terminal the bars are redrawn in place below the cursor, otherwise
+    // plain lines are written every PLAIN_INTERVAL and when a bar finishes.
+    fn draw(&mut self, force: bool, message: Option<&str>) {
+        let interval = if self.tty {
+            REDRAW_INTERVAL
+        } else {
+            PLAIN_INTERVAL
+        };
+        let due = self.last_draw.is_none_or(|t| t.elapsed() >= interval);
+        if !(force || due || message.is_some()) {
+            return;
+        }
+        let mut frame = String::new();
+        if self.tty {
+            if self.lines > 0 {
+                frame.push_str(&format!("\x1b[{}A", self.lines));
+            }
+            for line in message.into_iter().flat_map(|m| m.lines()) {
+                frame.push_str(&format!("\r\x1b[2K{}\n", line));
+            }
+            let lines = self.render(true);
+            for line in &lines {
+                frame.push_str(&format!("\r\x1b[2K{}\n", line));
+            }
+            self.lines = lines.len();
+        } else {
+            if let Some(msg) = message {
+                frame.push_str(&format!("{}\n", msg));
+            }
+            if force || due {
+                for line in self.render(false) {
+                    frame.push_str(&format!("{}\n", strip_ansi(&line)));
+                }
+            }
+        }
+        self.last_draw = Some(Instant::now());
+        let mut out = io::stdout().lock();
+        let _ = out.write_all(frame.as_bytes());
+        let _ = out.flush();
+    }
+}
+
+// Progress bar redrawn in place on stdout, showing percentage, counts,
+// throughput and ETA. Clones share the same bar, so it can be passed to
+// worker threads.
+#[derive(Clone)]
+pub struct ProgressBar {
+    state: Arc<Mutex<BarState>>,
+    target: Arc<Mutex<DrawState>>,
+}
+
+impl ProgressBar {
+    pub fn new(total: u64) -> Self {
+        MultiProgress::new().add(total)
+    }
+
+    pub fn label(self, label: &str) -> Self {
+        self.set_label(label);
+        self
+    }
+
+    pub fn set_label(&self, label: &str) {
+        self.update(false, |bar| bar.label = label.to_string());
+    }
+
+    pub fn inc(&self, delta: u64) {
+        self.update(false, |bar| {
+            bar.position = bar.position.saturating_add(delta)
+        });
+    }
+
+    pub fn set_position(&self, position: u64) {
+        self.update(false, |bar| bar.position = position);
+    }
+
+    pub fn position(&self) -> u64 {
+        self.state.lock().map_or(0, |bar| bar.position)
+    }
+
+    pub fn finish(&self) {
+        self.update(true, |bar| bar.finished = true);
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.state.lock().map_or(true, |bar| bar.finished)
+    }
+
+    // Prints a message above the bars without breaking them.
+    pub fn println(&self, msg: &str) {
+        if let Ok(mut target) = self.target.lock() {
+            target.draw(false, Some(msg));
+        }
+    }
+
+    fn update(&self, force: bool, f: impl FnOnce(&mut BarState)) {
+        if let Ok(mut bar) = self.state.lock() {
+            f(&mut bar);
+        }
+        if let Ok(mut target) = self.target.lock() {
+            target.draw(force, None);
+        }
+    }
+}
+
+// Several bars drawn together, one line each, e.g. one per worker.
+pub struct MultiProgress {
+    target: Arc<Mutex<DrawState>>,
+}
+
+impl Default for MultiProgress {
+    fn default() -> Self {
+        MultiProgress::new()
+    }
+}
+
+impl MultiProgress {
+    pub fn new() -> Self {
+        MultiProgress {
+            target: Arc::new(Mutex::new(DrawState {
+                bars: vec![],
+                lines: 0,
+                last_draw: None,
+                tty: io::stdout().is_terminal(),
+            })),
+        }
+    }
+
+    pub fn add(&self, total: u64) -> ProgressBar {
+        let state = Arc::new(Mutex::new(BarState {
+            label: String::new(),
+            position: 0,
+            total,
+            started: Instant::now(),
+            finished: false,
+            reported: false,
+        }));
+        if let Ok(mut target) = self.target.lock() {
+            target.bars.push(state.clone());
+        }
+        ProgressBar {
+            state,
+            target: self.target.clone(),
+        }
+    }
+
+    pub fn println(&self, msg: &str) {
+        if let Ok(mut target) = self.target.lock() {
+            target.draw(false, Some(msg));
+        }
+    }
+}
+
+// "label ━━━━━━────── 42% 420/1000 35.2/s ETA 00:16" fitted into width.
+pub fn str_progress(
+    label: &str,
+    position: u64,
+    total: u64,
+    elapsed: Duration,
+    finished: bool,
+    width: usize,
+) -> String {
+    let ratio = if total == 0 {
+        1.0
+    } else {
+        (position as f64 / total as f64).min(1.0)
+    };
+    let secs = elapsed.as_secs_f64();
+    let rate = if secs > 0.0 {
+        position as f64 / secs
+    } else {
+        0.0
+    };
+    let time = if finished {
+        format!("in {}", str_duration(elapsed))
+    } else if rate > 0.0 {
+        // Huge totals at slow rates overflow a Duration
+        let remaining = total.saturating_sub(position) as f64 / rate;
+        match Duration::try_from_secs_f64(remaining) {
+            Ok(remaining) => format!("ETA {}", str_duration(remaining)),
+            Err(_) => UNKNOWN_ETA.to_string(),
+        }
+    } else {
+        UNKNOWN_ETA.to_string()
+    };
+    let percent = format!("{:>3}%", (ratio * 100.0) as u64);
+    let counts = format!("{}/{}", position, total);
+    let rate = format!("{:.1}/s", rate);
+
+    let fixed = [
+        display_width(label),
+        percent.len(),
+        counts.len(),
+        rate.len(),
+        time.len(),
+    ];
+    let spaces = if label.is_empty() { 4 } else { 5 };
+    let bar_width = width
+        .saturating_sub(fixed.iter().sum::<usize>() + spaces)
+        .max(MIN_BAR_WIDTH);
+    let filled = (ratio * bar_width as f64).round() as usize;
+    let bar = "━".repeat(filled);
+    let bar = if finished {
+        bar.csuccess()
+    } else {
+        bar.citem()
+    };
+
+    let mut output = String::new();
+    if !label.is_empty() {
+        output.push_str(&format!("{} ", label.cinfo()));
+    }
+    output.push_str(&format!(
+        "{}{} {} {} {} {}",
+        bar,
+        "─".repeat(bar_width - filled).cline(),
+        percent.cvar(),
+        counts.cvar(),
+        rate.cinfo(),
+        time.cinfo()
+    ));
+    output
+}
+
+fn str_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    if secs >= 3600 {
+        format!("{}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+    } else {
+        format!("{:02}:{:02}", secs / 60, secs % 60)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn eta_overflow_is_unknown() {
+        let output = str_progress("", 1, u64::MAX, Duration::from_secs(1000), false, 80);
+        assert!(strip_ansi(&output).ends_with(UNKNOWN_ETA), "{}", output);
+    }
+
+    #[test]
+    fn labels_change_while_rendering() {
+        let progress = MultiProgress::new();
+        let bars: Vec<ProgressBar> = (0..3).map(|_| progress.add(10)).collect();
+        let state = bars[1].state.clone();
+        let writer = std::thread::spawn(move || {
+            for i in 0..2000 {
+                if let Ok(mut bar) = state.lock() {
+                    bar.label = "x".repeat(i % 40);
+                }
+            }
+        });
+        for _ in 0..2000 {
+            let lines = progress.target.lock().unwrap().render(true);
+            assert_eq!(lines.len(), 3);
+            let widths: Vec<usize> = lines.iter().map(|l| display_width(l)).collect();
+            assert!(widths.iter().all(|w| *w == widths[0]), "{:?}", lines);
+        }
+        writer.join().unwrap();
+    }
+
+    #[test]
+    fn non_ascii_labels_fill_the_width() {
+        for label in ["upload", "übertragung", "下载中"] {
+            let output = str_progress(label, 5, 10, Duration::from_secs(1), false, 60);
+            assert_eq!(display_width(&output), 60, "{}", output);
+        }
+    }
+}
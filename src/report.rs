@@ -0,0 +1,247 @@
+use std::backtrace::{Backtrace, BacktraceStatus};
+use std::error::Error;
+
+use crate::printer::Printer;
+use crate::termprint::ColoredItem;
+
+const CONTEXT_LINES: usize = 1;
+
+// Source excerpt pointing at the place an error refers to. `line` and `column`
+// are 1-based, `len` is the number of characters to underline.
+#[derive(Clone, Debug)]
+pub struct Snippet {
+    pub file: String,
+    pub source: String,
+    pub line: usize,
+    pub column: usize,
+    pub len: usize,
+    pub label: Option<String>,
+}
+
+impl Snippet {
+    pub fn new(file: &str, source: &str, line: usize, column: usize, len: usize) -> Self {
+        Snippet {
+            file: file.to_string(),
+            source: source.to_string(),
+            line,
+            column,
+            len,
+            label: None,
+        }
+    }
+
+    pub fn label(mut self, label: &str) -> Self {
+        self.label = Some(label.to_string());
+        self
+    }
+}
+
+// Error printer for any std::error::Error: the error, its chain of sources,
+// optional source snippets and a backtrace if one was captured.
+pub struct ErrorReport<'a> {
+    error: &'a dyn Error,
+    backtrace: Option<&'a Backtrace>,
+    snippets: Vec<Snippet>,
+}
+
+impl<'a> ErrorReport<'a> {
+    pub fn new(error: &'a dyn Error) -> Self {
+        ErrorReport {
+            error,
+            backtrace: None,
+            snippets: vec![],
+        }
+    }
+
+    pub fn backtrace(mut self, backtrace: &'a Backtrace) -> Self {
+        self.backtrace = Some(backtrace);
+        self
+    }
+
+    pub fn snippet(mut self, snippet: Snippet) -> Self {
+        self.snippets.push(snippet);
+        self
+    }
+
+    pub fn render(&self) -> String {
+        let mut output = format!(
+            "{} {}\n",
+            "ERROR".cerror(),
+            self.error.to_string().as_str().cerror()
+        );
+        for (i, cause) in error_chain(self.error).iter().skip(1).enumerate() {
+            output.push_str(&format!(
+                "  {} {}\n",
+                format!("{:>2}: caused by", i + 1).as_str().cinfo(),
+                cause.as_str().cvar()
+            ));
+        }
+        for snippet in &self.snippets {
+            output.push_str(&str_snippet(snippet));
+        }
+        if let Some(bt) = self.backtrace {
+            if bt.status() == BacktraceStatus::Captured {
+                output.push_str(&format!("{}\n", "Backtrace:".ctitle()));
+                output.push_str(&format!("{}\n", bt));
+            }
+        }
+        output
+    }
+
+    pub fn print(&self) {
+        let _ = Printer::stdout().report(self);
+    }
+}
+
+pub fn error_chain(error: &dyn Error) -> Vec<String> {
+    let mut chain = vec![error.to_string()];
+    let mut source = error.source();
+    while let Some(cause) = source {
+        chain.push(cause.to_string());
+        source = cause.source();
+    }
+    chain
+}
+
+pub fn str_error_report(error: &dyn Error) -> String {
+    ErrorReport::new(error).render()
+}
+
+pub fn print_error_report(error: &dyn Error) {
+    ErrorReport::new(error).print();
+}
+
+pub fn str_snippet(snippet: &Snippet) -> String {
+    let lines: Vec<&str> = snippet.source.lines().collect();
+    let line_idx = snippet
+        .line
+        .saturating_sub(1)
+        .min(lines.len().saturating_sub(1));
+    let first = line_idx.saturating_sub(CONTEXT_LINES);
+    let last = (line_idx + CONTEXT_LINES).min(lines.len().saturating_sub(1));
+    let gutter = (last + 1).to_string().len();
+    let bar = format!("{:gutter$} |", "").as_str().cline();
+
+    let mut output = format!(
+        "{:gutter$}{} {}:{}:{}\n",
+        "",
+        "-->".cline(),
+        snippet.file,
+        snippet.line,
+        snippet.column
+    );
+    output.push_str(&format!("{}\n", bar));
+    for (i, text) in lines.iter().enumerate().take(last + 1).skip(first) {
+        output.push_str(&format!(
+            "{} {}\n",
+            format!("{:>gutter$} |", i + 1).as_str().cline(),
+            text
+        ));
+        if i == line_idx {
+            let carets = format!(
+                "{}{}",
+                " ".repeat(snippet.column.saturating_sub(1)),
+                "^".repeat(snippet.len.max(1))
+            );
+            let label = snippet.label.clone().unwrap_or_default();
+            output.push_str(&format!(
+                "{} {} {}\n",
+                bar,
+                carets.as_str().cerror(),
+                label.as_str().cerror()
+            ));
+        }
+    }
+    output.push_str(&format!("{}\n", bar));
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fmt;
+
+    use super::*;
+    use crate::printer::strip_ansi;
+
+    #[derive(Debug)]
+    struct Layer(&'static str, Option<Box<Layer>>);
+
+    impl fmt::Display for Layer {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "{}", self.0)
+        }
+    }
+
+    impl Error for Layer {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            self.1.as_deref().map(|e| e as &(dyn Error + 'static))
+        }
+    }
+
+    const SOURCE: &str = "first = 1\nsecond = 2\nthird = 3";
+
+    fn snippet(line: usize, column: usize, len: usize) -> String {
+        let snippet = Snippet::new("a.toml", SOURCE, line, column, len).label("here");
+        strip_ansi(&str_snippet(&snippet))
+            .lines()
+            .map(|l| format!("{}\n", l.trim_end()))
+            .collect()
+    }
+
+    #[test]
+    fn chain_starts_with_the_error() {
+        let error = Layer(
+            "request failed",
+            Some(Box::new(Layer(
+                "connection reset",
+                Some(Box::new(Layer("os error 104", None))),
+            ))),
+        );
+        assert_eq!(
+            error_chain(&error),
+            vec!["request failed", "connection reset", "os error 104"]
+        );
+        assert_eq!(error_chain(&Layer("alone", None)), vec!["alone"]);
+
+        let output = strip_ansi(&str_error_report(&error));
+        assert_eq!(
+            output,
+            "ERROR request failed\n   1: caused by connection reset\n   2: caused by os error 104\n"
+        );
+    }
+
+    #[test]
+    fn snippet_marks_the_column() {
+        assert_eq!(
+            snippet(2, 10, 1),
+            " --> a.toml:2:10\n  |\n1 | first = 1\n2 | second = 2\n  |          ^ here\n3 | third = 3\n  |\n"
+        );
+    }
+
+    #[test]
+    fn snippet_on_first_and_last_line() {
+        assert_eq!(
+            snippet(1, 1, 5),
+            " --> a.toml:1:1\n  |\n1 | first = 1\n  | ^^^^^ here\n2 | second = 2\n  |\n"
+        );
+        assert_eq!(
+            snippet(3, 9, 0),
+            " --> a.toml:3:9\n  |\n2 | second = 2\n3 | third = 3\n  |         ^ here\n  |\n"
+        );
+    }
+
+    #[test]
+    fn snippet_out_of_range() {
+        // Past the end points at the last line, the location is kept as given
+        assert_eq!(
+            snippet(7, 20, 2),
+            " --> a.toml:7:20\n  |\n2 | second = 2\n3 | third = 3\n  |                    ^^ here\n  |\n"
+        );
+        assert_eq!(
+            snippet(0, 0, 1),
+            " --> a.toml:0:0\n  |\n1 | first = 1\n  | ^ here\n2 | second = 2\n  |\n"
+        );
+        let empty = strip_ansi(&str_snippet(&Snippet::new("e", "", 3, 1, 1)));
+        assert_eq!(empty, " --> e:3:1\n  |\n  |\n");
+    }
+}
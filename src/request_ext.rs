@@ -1,60 +1,379 @@
-use reqwest::Request;
 use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
 
+use http::header::{HeaderMap, HeaderValue};
+use http::method::InvalidMethod;
+use http::{Method, StatusCode, Version};
+
+use crate::redact::{redact_policy, RedactPolicy};
+
+pub const HEADER_VALUE_SEPARATOR: &str = " | ";
+pub const BINARY_MARKER: &str = "[bin]";
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub enum HttpMethod {
     GET,
     PUT,
     DELETE,
     POST,
+    PATCH,
+    HEAD,
+    OPTIONS,
+    CONNECT,
+    TRACE,
+    Extension(String),
 }
 
 impl HttpMethod {
-    fn as_str(&self) -> &'static str {
+    pub fn as_str(&self) -> &str {
         match self {
             HttpMethod::GET => "GET",
             HttpMethod::POST => "POST",
             HttpMethod::PUT => "PUT",
             HttpMethod::DELETE => "DELETE",
+            HttpMethod::PATCH => "PATCH",
+            HttpMethod::HEAD => "HEAD",
+            HttpMethod::OPTIONS => "OPTIONS",
+            HttpMethod::CONNECT => "CONNECT",
+            HttpMethod::TRACE => "TRACE",
+            HttpMethod::Extension(method) => method,
         }
     }
+}
+
+impl fmt::Display for HttpMethod {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for HttpMethod {
+    type Err = InvalidMethod;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Method::from_bytes(s.as_bytes()).map(HttpMethod::from)
+    }
+}
+
+// http::Method is re-exported as reqwest::Method, so these conversions cover both.
+impl From<&Method> for HttpMethod {
+    fn from(method: &Method) -> Self {
+        match *method {
+            Method::GET => HttpMethod::GET,
+            Method::POST => HttpMethod::POST,
+            Method::PUT => HttpMethod::PUT,
+            Method::DELETE => HttpMethod::DELETE,
+            Method::PATCH => HttpMethod::PATCH,
+            Method::HEAD => HttpMethod::HEAD,
+            Method::OPTIONS => HttpMethod::OPTIONS,
+            Method::CONNECT => HttpMethod::CONNECT,
+            Method::TRACE => HttpMethod::TRACE,
+            _ => HttpMethod::Extension(method.as_str().to_string()),
+        }
+    }
+}
+
+impl From<Method> for HttpMethod {
+    fn from(method: Method) -> Self {
+        HttpMethod::from(&method)
+    }
+}
+
+impl TryFrom<HttpMethod> for Method {
+    type Error = InvalidMethod;
+
+    fn try_from(method: HttpMethod) -> Result<Self, Self::Error> {
+        Method::from_bytes(method.as_str().as_bytes())
+    }
+}
+
+// Body access for the request types the HTTP printers accept. Bodies that are
+// streamed or not held in memory return None.
+pub trait BodyBytes {
+    fn body_bytes(&self) -> Option<&[u8]>;
+}
+
+impl BodyBytes for () {
+    fn body_bytes(&self) -> Option<&[u8]> {
+        None
+    }
+}
+
+impl BodyBytes for Vec<u8> {
+    fn body_bytes(&self) -> Option<&[u8]> {
+        Some(self)
+    }
+}
+
+impl BodyBytes for &[u8] {
+    fn body_bytes(&self) -> Option<&[u8]> {
+        Some(self)
+    }
+}
+
+impl BodyBytes for String {
+    fn body_bytes(&self) -> Option<&[u8]> {
+        Some(self.as_bytes())
+    }
+}
+
+impl BodyBytes for &str {
+    fn body_bytes(&self) -> Option<&[u8]> {
+        Some(self.as_bytes())
+    }
+}
+
+impl BodyBytes for bytes::Bytes {
+    fn body_bytes(&self) -> Option<&[u8]> {
+        Some(self)
+    }
+}
+
+#[cfg(feature = "reqwest")]
+impl BodyBytes for reqwest::Body {
+    fn body_bytes(&self) -> Option<&[u8]> {
+        self.as_bytes()
+    }
+}
+
+// Common view on http::Request, reqwest::Request and reqwest::blocking::Request.
+pub trait RequestParts {
+    fn http_method(&self) -> HttpMethod;
+    fn http_url(&self) -> String;
+    fn http_headers(&self) -> &HeaderMap;
+    fn http_body(&self) -> Option<&[u8]>;
+}
+
+// Common view on http::Response, reqwest::Response and reqwest::blocking::Response.
+// http::Response does not know its URL.
+pub trait ResponseParts {
+    fn http_status(&self) -> StatusCode;
+    fn http_version(&self) -> Version;
+    fn http_url(&self) -> Option<String>;
+    fn http_headers(&self) -> &HeaderMap;
+}
+
+impl<B: BodyBytes> RequestParts for http::Request<B> {
+    fn http_method(&self) -> HttpMethod {
+        HttpMethod::from(self.method())
+    }
+
+    fn http_url(&self) -> String {
+        self.uri().to_string()
+    }
+
+    fn http_headers(&self) -> &HeaderMap {
+        self.headers()
+    }
+
+    fn http_body(&self) -> Option<&[u8]> {
+        self.body().body_bytes()
+    }
+}
+
+impl<B> ResponseParts for http::Response<B> {
+    fn http_status(&self) -> StatusCode {
+        self.status()
+    }
 
-    fn to_string(&self) -> String {
-        self.as_str().to_string()
+    fn http_version(&self) -> Version {
+        self.version()
+    }
+
+    fn http_url(&self) -> Option<String> {
+        None
+    }
+
+    fn http_headers(&self) -> &HeaderMap {
+        self.headers()
+    }
+}
+
+#[cfg(feature = "reqwest")]
+impl RequestParts for reqwest::Request {
+    fn http_method(&self) -> HttpMethod {
+        HttpMethod::from(self.method())
+    }
+
+    fn http_url(&self) -> String {
+        self.url().to_string()
+    }
+
+    fn http_headers(&self) -> &HeaderMap {
+        self.headers()
+    }
+
+    fn http_body(&self) -> Option<&[u8]> {
+        self.body().and_then(|b| b.as_bytes())
+    }
+}
+
+#[cfg(feature = "reqwest")]
+impl ResponseParts for reqwest::Response {
+    fn http_status(&self) -> StatusCode {
+        self.status()
+    }
+
+    fn http_version(&self) -> Version {
+        self.version()
+    }
+
+    fn http_url(&self) -> Option<String> {
+        Some(self.url().to_string())
+    }
+
+    fn http_headers(&self) -> &HeaderMap {
+        self.headers()
+    }
+}
+
+#[cfg(feature = "blocking")]
+impl RequestParts for reqwest::blocking::Request {
+    fn http_method(&self) -> HttpMethod {
+        HttpMethod::from(self.method())
+    }
+
+    fn http_url(&self) -> String {
+        self.url().to_string()
+    }
+
+    fn http_headers(&self) -> &HeaderMap {
+        self.headers()
+    }
+
+    fn http_body(&self) -> Option<&[u8]> {
+        self.body().and_then(|b| b.as_bytes())
     }
 }
 
-pub fn response_to_hashmap(
-    method: HttpMethod,
-    response: &reqwest::Response,
+#[cfg(feature = "blocking")]
+impl ResponseParts for reqwest::blocking::Response {
+    fn http_status(&self) -> StatusCode {
+        self.status()
+    }
+
+    fn http_version(&self) -> Version {
+        self.version()
+    }
+
+    fn http_url(&self) -> Option<String> {
+        Some(self.url().to_string())
+    }
+
+    fn http_headers(&self) -> &HeaderMap {
+        self.headers()
+    }
+}
+
+pub fn response_to_hashmap<M: Into<HttpMethod>, R: ResponseParts>(
+    method: M,
+    response: &R,
 ) -> HashMap<String, String> {
+    let policy = redact_policy();
     let mut map_resp: HashMap<String, String> = HashMap::new();
-    map_resp.insert("Status".to_string(), response.status().to_string());
-    map_resp.insert("Method".to_string(), method.to_string());
-    map_resp.insert("URL".to_string(), response.url().to_string());
-    for (key, value) in response.headers() {
-        map_resp.insert(key.to_string(), value.to_str().unwrap().to_string());
+    map_resp.insert("Status".to_string(), response.http_status().to_string());
+    map_resp.insert("Method".to_string(), method.into().to_string());
+    if let Some(url) = response.http_url() {
+        map_resp.insert("URL".to_string(), policy.redact_url(&url));
     }
+    map_resp.extend(headers_to_hashmap(response.http_headers()));
     map_resp
 }
 
-pub fn headers_to_hashmap(headers: &reqwest::header::HeaderMap) -> HashMap<String, String> {
+// Multi-valued headers (e.g. Set-Cookie) are joined instead of overwriting each other.
+pub fn headers_to_hashmap(headers: &HeaderMap) -> HashMap<String, String> {
+    redacted_headers(headers, &redact_policy())
+}
+
+pub(crate) fn redacted_headers(
+    headers: &HeaderMap,
+    policy: &RedactPolicy,
+) -> HashMap<String, String> {
     let mut map_headers: HashMap<String, String> = HashMap::new();
-    for (key, value) in headers {
-        map_headers.insert(key.to_string(), value.to_str().unwrap().to_string());
+    for key in headers.keys() {
+        let values = headers
+            .get_all(key)
+            .iter()
+            .map(|value| policy.redact(key.as_str(), &header_value_to_string(value)))
+            .collect::<Vec<String>>();
+        map_headers.insert(key.to_string(), values.join(HEADER_VALUE_SEPARATOR));
     }
     map_headers
 }
 
-pub fn request_to_hashmap(request: &Request) -> HashMap<String, String> {
-    let mut map_req: HashMap<String, String> = HashMap::new();
-    map_req.insert("Method".to_string(), request.method().to_string());
-    map_req.insert("URL".to_string(), request.url().to_string());
-    for (key, value) in request.headers() {
-        if key.as_str().eq_ignore_ascii_case("authorization") {
-            map_req.insert(key.to_string(), "********".to_string());
-        } else {
-            map_req.insert(key.to_string(), value.to_str().unwrap().to_string());
+// Header values may carry obsolete or binary bytes. Printable UTF-8 is shown
+// as is, anything else is hex-escaped and marked with BINARY_MARKER.
+pub fn header_value_to_string(value: &HeaderValue) -> String {
+    if let Ok(s) = value.to_str() {
+        return s.to_string();
+    }
+    match std::str::from_utf8(value.as_bytes()) {
+        Ok(s) if !s.chars().any(|c| c.is_control() && c != '\t') => s.to_string(),
+        _ => {
+            let escaped: String = value
+                .as_bytes()
+                .iter()
+                .map(|b| match b {
+                    b' '..=b'~' if *b != b'\\' => (*b as char).to_string(),
+                    _ => format!("\\x{:02x}", b),
+                })
+                .collect();
+            format!("{} {}", BINARY_MARKER, escaped)
         }
     }
+}
+
+pub fn request_to_hashmap<R: RequestParts>(request: &R) -> HashMap<String, String> {
+    let policy = redact_policy();
+    let mut map_req: HashMap<String, String> = HashMap::new();
+    map_req.insert("Method".to_string(), request.http_method().to_string());
+    map_req.insert("URL".to_string(), policy.redact_url(&request.http_url()));
+    map_req.extend(headers_to_hashmap(request.http_headers()));
     map_req
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn binary_header_values_are_hex_escaped() {
+        let value = HeaderValue::from_bytes(b"ab\\\xff\xfe").unwrap();
+        assert_eq!(header_value_to_string(&value), "[bin] ab\\x5c\\xff\\xfe");
+    }
+
+    #[test]
+    fn utf8_header_values_are_kept() {
+        let value = HeaderValue::from_bytes("café".as_bytes()).unwrap();
+        assert_eq!(header_value_to_string(&value), "café");
+        let value = HeaderValue::from_static("text/plain");
+        assert_eq!(header_value_to_string(&value), "text/plain");
+    }
+
+    #[test]
+    fn multi_valued_headers_are_joined() {
+        let mut headers = HeaderMap::new();
+        headers.append("x-trace", HeaderValue::from_static("a"));
+        headers.append("x-trace", HeaderValue::from_static("b"));
+        headers.insert("accept", HeaderValue::from_static("*/*"));
+        let map = headers_to_hashmap(&headers);
+        assert_eq!(map["x-trace"], format!("a{}b", HEADER_VALUE_SEPARATOR));
+        assert_eq!(map["accept"], "*/*");
+    }
+
+    #[test]
+    fn extension_methods_round_trip() {
+        let method: HttpMethod = "PURGE".parse().unwrap();
+        assert_eq!(method, HttpMethod::Extension("PURGE".to_string()));
+        assert_eq!(method.to_string(), "PURGE");
+
+        let http_method = Method::try_from(method.clone()).unwrap();
+        assert_eq!(http_method.as_str(), "PURGE");
+        assert_eq!(HttpMethod::from(&http_method), method);
+
+        assert_eq!(HttpMethod::from(Method::PATCH), HttpMethod::PATCH);
+        assert!("BAD METHOD".parse::<HttpMethod>().is_err());
+        assert!(Method::try_from(HttpMethod::Extension("BAD METHOD".into())).is_err());
+    }
+}
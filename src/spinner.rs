@@ -0,0 +1,188 @@
+use std::io::{self, IsTerminal, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use crate::printer::strip_ansi;
+use crate::termprint::ColoredItem;
+
+const FRAMES: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+const FRAME_INTERVAL: Duration = Duration::from_millis(80);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    Success,
+    Warning,
+    Failure,
+}
+
+impl Outcome {
+    fn symbol(&self) -> String {
+        match self {
+            Outcome::Success => "✔".csuccess().to_string(),
+            Outcome::Warning => "⚠".cwarning().to_string(),
+            Outcome::Failure => "✘".cerror().to_string(),
+        }
+    }
+}
+
+// Final line of a step: "✔ message 1.2s".
+pub fn str_outcome(outcome: Outcome, msg: &str, elapsed: Duration) -> String {
+    let msg = match outcome {
+        Outcome::Success => msg.cvar(),
+        Outcome::Warning => msg.cwarning(),
+        Outcome::Failure => msg.cerror(),
+    };
+    format!(
+        "{} {} {}",
+        outcome.symbol(),
+        msg,
+        format!("{:.1?}", elapsed).as_str().cinfo()
+    )
+}
+
+fn str_spinner(frame: usize, msg: &str, elapsed: Duration) -> String {
+    format!(
+        "{} {} {}",
+        FRAMES[frame % FRAMES.len()].citem(),
+        msg.cvar(),
+        format!("{:.0?}", elapsed).as_str().cinfo()
+    )
+}
+
+fn str_static(msg: &str) -> String {
+    strip_ansi(&format!("{} {}", "-".cbullet(), msg.cvar()))
+}
+
+fn write_stdout(s: &str) {
+    let mut out = io::stdout().lock();
+    let _ = out.write_all(s.as_bytes());
+    let _ = out.flush();
+}
+
+// Status line for a step of unknown length. A background thread animates it
+// on a terminal; otherwise the message is printed once as a static line.
+// Finish with success, warning or fail; dropping it clears the line.
+pub struct Spinner {
+    message: Arc<Mutex<String>>,
+    started: Instant,
+    stop: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+    tty: bool,
+}
+
+impl Spinner {
+    pub fn new(msg: &str) -> Self {
+        let tty = io::stdout().is_terminal();
+        let mut spinner = Spinner {
+            message: Arc::new(Mutex::new(msg.to_string())),
+            started: Instant::now(),
+            stop: Arc::new(AtomicBool::new(false)),
+            handle: None,
+            tty,
+        };
+        if tty {
+            let message = spinner.message.clone();
+            let stop = spinner.stop.clone();
+            let started = spinner.started;
+            spinner.handle = Some(thread::spawn(move || {
+                let mut frame = 0;
+                while !stop.load(Ordering::Relaxed) {
+                    let msg = message.lock().map(|m| m.clone()).unwrap_or_default();
+                    write_stdout(&format!(
+                        "\r\x1b[2K{}",
+                        str_spinner(frame, &msg, started.elapsed())
+                    ));
+                    frame += 1;
+                    thread::sleep(FRAME_INTERVAL);
+                }
+            }));
+        } else {
+            write_stdout(&format!("{}\n", str_static(msg)));
+        }
+        spinner
+    }
+
+    pub fn set_message(&self, msg: &str) {
+        if let Ok(mut m) = self.message.lock() {
+            *m = msg.to_string();
+        }
+        if !self.tty {
+            write_stdout(&format!("{}\n", str_static(msg)));
+        }
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    pub fn success(mut self, msg: &str) {
+        self.finish(Outcome::Success, msg);
+    }
+
+    pub fn warning(mut self, msg: &str) {
+        self.finish(Outcome::Warning, msg);
+    }
+
+    pub fn fail(mut self, msg: &str) {
+        self.finish(Outcome::Failure, msg);
+    }
+
+    fn stop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+            write_stdout("\r\x1b[2K");
+        }
+    }
+
+    fn finish(&mut self, outcome: Outcome, msg: &str) {
+        self.stop();
+        let line = str_outcome(outcome, msg, self.started.elapsed());
+        if self.tty {
+            write_stdout(&format!("{}\n", line));
+        } else {
+            write_stdout(&format!("{}\n", strip_ansi(&line)));
+        }
+    }
+}
+
+impl Drop for Spinner {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frames_cycle() {
+        let frames: Vec<String> = (0..FRAMES.len() + 2)
+            .map(|i| strip_ansi(&str_spinner(i, "loading", Duration::from_millis(1600))))
+            .collect();
+        assert_eq!(frames[0], "⠋ loading 2s");
+        assert_eq!(frames[1], "⠙ loading 2s");
+        assert_eq!(frames[9], "⠏ loading 2s");
+        // Wraps around after the last frame
+        assert_eq!(frames[10], frames[0]);
+        assert_eq!(frames[11], frames[1]);
+    }
+
+    #[test]
+    fn final_status_lines() {
+        let elapsed = Duration::from_millis(1234);
+        let line = |outcome| strip_ansi(&str_outcome(outcome, "done", elapsed));
+        assert_eq!(line(Outcome::Success), "✔ done 1.2s");
+        assert_eq!(line(Outcome::Warning), "⚠ done 1.2s");
+        assert_eq!(line(Outcome::Failure), "✘ done 1.2s");
+    }
+
+    #[test]
+    fn static_lines_are_plain() {
+        colored::control::set_override(true);
+        assert_eq!(str_static("loading"), "- loading");
+    }
+}
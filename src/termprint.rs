@@ -1,19 +1,62 @@
 use std::cmp::min;
 use std::fmt;
 
-use colored::{Color, ColoredString, Colorize};
+use colored::{ColoredString, Colorize};
 use std::collections::HashMap;
 use time::OffsetDateTime;
 
 use serde_json::Value;
 
-use reqwest::{Request, Response};
-
 use termsize::Size;
 
+use crate::printer::{scoped_width, split_width};
+use crate::redact::redact_policy;
 use crate::request_ext::{headers_to_hashmap, request_to_hashmap, response_to_hashmap};
-
+use crate::theme::with_theme;
+
+pub use crate::request_ext::{BodyBytes, RequestParts, ResponseParts};
+
+pub use crate::color::{
+    ansi16_color, color_distance, contrast_ratio, detect_background, detect_color_support,
+    ensure_contrast, fg_code, gradient, gradient_at, hsl2rgb, is_readable, lerp_rgb, luminance,
+    oklch2rgb, palette, query_background_color, rgb2ansi16, rgb2hsl, rgb2index, rgb2oklch,
+    Background, ColorSupport,
+};
+#[cfg(feature = "reqwest")]
+pub use crate::exchange::send_exchange;
+#[cfg(feature = "blocking")]
+pub use crate::exchange::send_exchange_blocking;
+pub use crate::exchange::{
+    exchange_response_to_hashmap, print_exchange, print_exchange_response, str_exchange,
+    str_exchange_response, str_exchange_summary, str_status, BodyLimits, HttpExchange,
+};
+pub use crate::export::{
+    exchange_to_curl, print_curl, request_to_curl, shell_quote, CurlOptions, HarRecorder,
+};
+pub use crate::layout::{print_columns, str_columns, ColumnWidth, Columns};
+#[cfg(feature = "log")]
+pub use crate::log_ext::{init_logger, TermprintLogger, RUST_LOG_ENV};
+pub use crate::logger::{
+    log, log_config, log_debug, log_error, log_info, log_trace, log_warn, set_log_config, str_log,
+    Level, LogConfig, TargetFilter, LOG_LEVEL_ENV,
+};
+#[cfg(feature = "middleware")]
+pub use crate::middleware::{PrintMiddleware, Verbosity};
+pub use crate::panel::{print_panel, str_panel, wrap, Align, Border, Panel};
+pub use crate::printer::{
+    clear_thread_prefix, colors_enabled, display_width, set_thread_prefix, str_prefix, strip_ansi,
+    thread_prefix, ColorPolicy, Printer,
+};
+pub use crate::progress::{str_progress, MultiProgress, ProgressBar};
+pub use crate::redact::{set_redact_policy, RedactPolicy, Reveal};
+pub use crate::report::{
+    error_chain, print_error_report, str_error_report, str_snippet, ErrorReport, Snippet,
+};
 pub use crate::request_ext::HttpMethod;
+pub use crate::spinner::{str_outcome, Outcome, Spinner};
+pub use crate::theme::{set_theme, theme, Style, Theme};
+#[cfg(feature = "tracing")]
+pub use crate::tracing_ext::TermprintLayer;
 
 pub const LONG: usize = 120;
 pub const MEDIUM: usize = 80;
@@ -24,244 +67,75 @@ pub const RESET: &str = "\x1b[0m";
 pub const MAX_WIDTH: usize = 120;
 pub const MAX_COL_WIDTH: usize = 10;
 
-trait ColoredItem {
+pub(crate) trait ColoredItem {
     fn cinfo(&self) -> ColoredString;
     fn cvar(&self) -> ColoredString;
     fn ctitle(&self) -> ColoredString;
     // fn cheader(&self) -> ColoredString;
     fn cerror(&self) -> ColoredString;
     fn cwarning(&self) -> ColoredString;
+    fn csuccess(&self) -> ColoredString;
     fn citem(&self) -> ColoredString;
     fn cline(&self) -> ColoredString;
     fn cbullet(&self) -> ColoredString;
     fn column(&self, index: usize) -> ColoredString;
 }
 
+// Column colors of the feature's theme before it is adapted to the background
 #[cfg(any(feature = "truecolors", feature = "colors256"))]
-pub const COLUMN_COLORS: [(u8, u8, u8); 10] = [
-    (255, 0, 0),     // Red
-    (0, 255, 0),     // Green
-    (0, 0, 255),     // Blue
-    (255, 255, 0),   // Yellow
-    (0, 255, 255),   // Cyan
-    (255, 0, 255),   // Magenta
-    (192, 192, 192), // Silver
-    (128, 128, 128), // Gray
-    (128, 0, 0),     // Maroon
-    (128, 128, 0),   // Olive
-];
+pub const COLUMN_COLORS: [(u8, u8, u8); 10] = crate::theme::TRUECOLOR_COLUMNS;
 
 #[cfg(feature = "mono")]
-pub const COLUMN_COLORS: [(u8, u8, u8); 3] = [(75, 75, 75), (150, 150, 150), (255, 255, 255)];
-
-#[cfg(feature = "basic")]
-pub const COLUMN_COLORS: [Color; 5] = [
-    Color::BrightBlue,
-    Color::BrightCyan,
-    Color::BrightMagenta,
-    Color::BrightGreen,
-    Color::BrightYellow,
-];
-
-#[cfg(feature = "basic")]
-impl ColoredItem for &str {
-    fn cinfo(&self) -> ColoredString {
-        self.blue()
-    }
-
-    fn cvar(&self) -> ColoredString {
-        self.bright_cyan()
-    }
-
-    fn ctitle(&self) -> ColoredString {
-        self.bold().blue()
-    }
-
-    fn cerror(&self) -> ColoredString {
-        self.bright_red().bold()
-    }
-
-    fn cwarning(&self) -> ColoredString {
-        self.bright_yellow()
-    }
-
-    fn citem(&self) -> ColoredString {
-        self.bright_cyan()
-    }
-
-    fn cline(&self) -> ColoredString {
-        self.bright_blue().bold()
-    }
-
-    fn cbullet(&self) -> ColoredString {
-        self.bright_blue().bold()
-    }
-
-    fn column(&self, index: usize) -> ColoredString {
-        let col_color = COLUMN_COLORS[index % COLUMN_COLORS.len()];
-        self.color(col_color)
-    }
-}
+pub const COLUMN_COLORS: [(u8, u8, u8); 3] = crate::theme::MONO_COLUMNS;
 
 #[cfg(feature = "basic")]
-impl ColoredItem for String {
-    fn cinfo(&self) -> ColoredString {
-        self.blue()
-    }
-
-    fn cvar(&self) -> ColoredString {
-        self.bright_cyan()
-    }
-
-    fn ctitle(&self) -> ColoredString {
-        self.bold().blue()
-    }
-
-    fn cerror(&self) -> ColoredString {
-        self.bright_red().bold()
-    }
-
-    fn cwarning(&self) -> ColoredString {
-        self.bright_yellow()
-    }
-
-    fn citem(&self) -> ColoredString {
-        self.bright_cyan()
-    }
-
-    fn cline(&self) -> ColoredString {
-        self.bright_blue().bold()
-    }
-
-    fn cbullet(&self) -> ColoredString {
-        self.bright_blue().bold()
-    }
+pub const COLUMN_COLORS: [colored::Color; 5] = crate::theme::BASIC_COLUMNS;
 
-    fn column(&self, index: usize) -> ColoredString {
-        let col_color = COLUMN_COLORS[index % COLUMN_COLORS.len()];
-        self.color(col_color)
-    }
-}
-
-#[cfg(feature = "colors256")]
-impl ColoredItem for &str {
+// Roles resolve to the theme in effect: the rendering Printer's theme or the global one.
+impl<T: AsRef<str> + ?Sized> ColoredItem for T {
     fn cinfo(&self) -> ColoredString {
-        self.truecolor(51, 102, 255)
+        with_theme(|t| t.paint(t.info, self.as_ref()))
     }
 
     fn cvar(&self) -> ColoredString {
-        self.truecolor(0, 255, 255)
+        with_theme(|t| t.paint(t.var, self.as_ref()))
     }
 
     fn ctitle(&self) -> ColoredString {
-        self.truecolor(51, 102, 255)
+        with_theme(|t| t.paint(t.title, self.as_ref()))
     }
 
     fn cerror(&self) -> ColoredString {
-        self.bold().truecolor(255, 0, 0)
+        with_theme(|t| t.paint(t.error, self.as_ref()))
     }
 
     fn cwarning(&self) -> ColoredString {
-        self.truecolor(255, 255, 85)
-    }
-
-    fn citem(&self) -> ColoredString {
-        self.truecolor(0, 255, 255)
-    }
-
-    fn cline(&self) -> ColoredString {
-        self.truecolor(51, 102, 255)
-    }
-
-    fn cbullet(&self) -> ColoredString {
-        self.truecolor(51, 102, 255)
-    }
-
-    fn column(&self, index: usize) -> ColoredString {
-        let color = COLUMN_COLORS[index % COLUMN_COLORS.len()];
-        self.truecolor(color.0, color.1, color.2)
-    }
-}
-
-#[cfg(feature = "truecolors")]
-impl ColoredItem for &str {
-    fn cinfo(&self) -> ColoredString {
-        self.truecolor(51, 102, 255)
-    }
-
-    fn cvar(&self) -> ColoredString {
-        self.truecolor(0, 255, 255)
-    }
-
-    fn ctitle(&self) -> ColoredString {
-        self.truecolor(51, 102, 255)
+        with_theme(|t| t.paint(t.warning, self.as_ref()))
     }
 
-    fn cerror(&self) -> ColoredString {
-        self.bold().truecolor(255, 0, 0)
-    }
-
-    fn cwarning(&self) -> ColoredString {
-        self.truecolor(255, 255, 85)
+    fn csuccess(&self) -> ColoredString {
+        with_theme(|t| t.paint(t.success, self.as_ref()))
     }
 
     fn citem(&self) -> ColoredString {
-        self.truecolor(0, 255, 255)
+        with_theme(|t| t.paint(t.item, self.as_ref()))
     }
 
     fn cline(&self) -> ColoredString {
-        self.truecolor(51, 102, 255)
+        with_theme(|t| t.paint(t.line, self.as_ref()))
     }
 
     fn cbullet(&self) -> ColoredString {
-        self.truecolor(51, 102, 255)
+        with_theme(|t| t.paint(t.bullet, self.as_ref()))
     }
 
     fn column(&self, index: usize) -> ColoredString {
-        let color = COLUMN_COLORS[index % COLUMN_COLORS.len()];
-        self.truecolor(color.0, color.1, color.2)
+        with_theme(|t| t.paint(t.column(index), self.as_ref()))
     }
 }
 
-#[cfg(feature = "mono")]
-impl ColoredItem for &str {
-    fn cinfo(&self) -> ColoredString {
-        self.white()
-    }
-
-    fn cvar(&self) -> ColoredString {
-        self.white()
-    }
-
-    fn ctitle(&self) -> ColoredString {
-        self.white()
-    }
-
-    fn cerror(&self) -> ColoredString {
-        self.bold().white()
-    }
-
-    fn cwarning(&self) -> ColoredString {
-        self.white()
-    }
-
-    fn citem(&self) -> ColoredString {
-        self.white()
-    }
-
-    fn cline(&self) -> ColoredString {
-        self.white()
-    }
-
-    fn cbullet(&self) -> ColoredString {
-        self.truecolor(51, 102, 255)
-    }
-
-    fn column(&self, index: usize) -> ColoredString {
-        let color = COLUMN_COLORS[index % COLUMN_MONO_COLORS.len()];
-        self.truecolor(color.0, color.1, color.2)
-    }
-}
+// Channel levels of xterm's 6x6x6 color cube
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
 
 pub fn index2rgb(index: usize) -> (u8, u8, u8) {
     if index < 16 {
@@ -285,10 +159,10 @@ pub fn index2rgb(index: usize) -> (u8, u8, u8) {
             _ => (0, 0, 0), // Default to black if out of range
         }
     } else if index < 232 {
-        let r = ((index - 16) / 36 % 6) * 51;
-        let g = ((index - 16) / 6 % 6) * 51;
-        let b = ((index - 16) % 6) * 51;
-        (r as u8, g as u8, b as u8)
+        let r = CUBE_LEVELS[(index - 16) / 36 % 6];
+        let g = CUBE_LEVELS[(index - 16) / 6 % 6];
+        let b = CUBE_LEVELS[(index - 16) % 6];
+        (r, g, b)
     } else {
         // Grayscale range (232-255)
         let gray = (index as i16 - 232) * 10 + 8;
@@ -297,23 +171,31 @@ pub fn index2rgb(index: usize) -> (u8, u8, u8) {
 }
 
 pub fn get_terminal_type() -> String {
-    let term = std::env::var("TERM").unwrap_or_else(|_| "unknown".to_string());
-    term
+    std::env::var("TERM").unwrap_or_else(|_| "unknown".to_string())
 }
 
+// Width of the rendering Printer if it has one, else of the terminal.
 pub fn get_terminal_width() -> usize {
+    if let Some(width) = scoped_width() {
+        return width;
+    }
     termsize::get()
         .unwrap_or(Size { rows: 1, cols: 100 })
         .cols
         .into()
 }
 
+pub fn str_terminal_type() -> String {
+    format!(
+        "{}{}\n{}\n",
+        double_line(MEDIUM),
+        info("Terminal type", &get_terminal_type()),
+        double_line(MEDIUM)
+    )
+}
+
 pub fn print_terminal_type() {
-    let term = get_terminal_type();
-    print_double_line(MEDIUM);
-    print_info("Terminal type", &term);
-    print_double_line(MEDIUM);
-    println!();
+    let _ = Printer::stdout().terminal_type();
 }
 // UNUSED
 // fn truncate_str(s: &str, max_len: usize) -> String {
@@ -325,26 +207,99 @@ pub fn print_terminal_type() {
 //     }
 // }
 
-pub fn print_all_colors() {
-    print_title("All Clorored Colors");
-    println!("{} {}", "Black".black(), "Bright Black".bright_black());
-    println!("{} {}", "Red".red(), "Bright Red".bright_red());
-    println!("{} {}", "Green".green(), "Bright Green".bright_green());
-    println!("{} {}", "Yellow".yellow(), "Bright Yellow".bright_yellow());
-    println!("{} {}", "Blue".blue(), "Bright Blue".bright_blue());
-    println!(
-        "{} {}",
+pub fn str_all_colors() -> String {
+    let mut output = str_title("All Clorored Colors");
+    output.push_str(&format!(
+        "{} {}\n",
+        "Black".black(),
+        "Bright Black".bright_black()
+    ));
+    output.push_str(&format!("{} {}\n", "Red".red(), "Bright Red".bright_red()));
+    output.push_str(&format!(
+        "{} {}\n",
+        "Green".green(),
+        "Bright Green".bright_green()
+    ));
+    output.push_str(&format!(
+        "{} {}\n",
+        "Yellow".yellow(),
+        "Bright Yellow".bright_yellow()
+    ));
+    output.push_str(&format!(
+        "{} {}\n",
+        "Blue".blue(),
+        "Bright Blue".bright_blue()
+    ));
+    output.push_str(&format!(
+        "{} {}\n",
         "Magenta".magenta(),
         "Bright Magenta".bright_magenta()
-    );
-    println!("{} {}", "Cyan".cyan(), "Bright Cyan".bright_cyan());
-    println!("{} {}", "White".white(), "Bright White".bright_white());
-    println!();
+    ));
+    output.push_str(&format!(
+        "{} {}\n",
+        "Cyan".cyan(),
+        "Bright Cyan".bright_cyan()
+    ));
+    output.push_str(&format!(
+        "{} {}\n",
+        "White".white(),
+        "Bright White".bright_white()
+    ));
+    output.push('\n');
+    for background in [Background::Dark, Background::Light] {
+        output.push_str(&str_theme_preview(
+            &Theme::for_background(background),
+            background,
+        ));
+    }
+    output
 }
 
-pub fn print_index2rgb() {
-    print_message("\nColor index to rgb:");
-    print_line(MEDIUM);
+// Roles and column colors of a theme on the given background.
+pub fn str_theme_preview(theme: &Theme, background: Background) -> String {
+    let (r, g, b) = background.rgb();
+    let roles = [
+        ("info", theme.info),
+        ("var", theme.var),
+        ("title", theme.title),
+        ("error", theme.error),
+        ("warning", theme.warning),
+        ("success", theme.success),
+        ("item", theme.item),
+        ("line", theme.line),
+        ("bullet", theme.bullet),
+    ];
+    let label = match background {
+        Background::Dark => "Dark background",
+        Background::Light => "Light background",
+    };
+    let mut output = format!("{}\n", message(label));
+    let mut row = |cells: Vec<ColoredString>| {
+        output.push_str(&format!("{}", " ".on_truecolor(r, g, b)));
+        for cell in cells {
+            output.push_str(&format!("{}", cell.on_truecolor(r, g, b)));
+            output.push_str(&format!("{}", " ".on_truecolor(r, g, b)));
+        }
+        output.push('\n');
+    };
+    row(roles
+        .iter()
+        .map(|(name, style)| theme.paint(*style, name))
+        .collect());
+    row((0..theme.columns.len())
+        .map(|i| theme.paint(theme.column(i), &format!("col{}", i)))
+        .collect());
+    output.push('\n');
+    output
+}
+
+pub fn print_all_colors() {
+    let _ = Printer::stdout().write_str(&str_all_colors());
+}
+
+pub fn str_index2rgb() -> String {
+    let mut output = format!("{}\n", message("\nColor index to rgb:"));
+    output.push_str(&line(MEDIUM));
     for i in 0..65 {
         let rgb1 = index2rgb(i);
         let rgb2 = index2rgb(i + 65);
@@ -354,27 +309,19 @@ pub fn print_index2rgb() {
         let rgb2_str: String = format!("{:3}:({:3},{:3},{:3})  ", i + 65, rgb2.0, rgb2.1, rgb2.2);
         let rgb3_str: String = format!("{:3}:({:3},{:3},{:3})  ", i + 130, rgb3.0, rgb3.1, rgb3.2);
         let rgb4_str: String = format!("{:3}:({:3},{:3},{:3})  ", i + 195, rgb4.0, rgb4.1, rgb4.2);
-        print!(
-            "{} ",
-            &rgb1_str.truecolor(rgb1.0 as u8, rgb1.1 as u8, rgb1.2 as u8)
-        );
-        print!(
-            "{} ",
-            &rgb2_str.truecolor(rgb2.0 as u8, rgb2.1 as u8, rgb2.2 as u8)
-        );
-        print!(
-            "{} ",
-            &rgb3_str.truecolor(rgb3.0 as u8, rgb3.1 as u8, rgb3.2 as u8)
-        );
+        output.push_str(&format!("{} ", &rgb1_str.truecolor(rgb1.0, rgb1.1, rgb1.2)));
+        output.push_str(&format!("{} ", &rgb2_str.truecolor(rgb2.0, rgb2.1, rgb2.2)));
+        output.push_str(&format!("{} ", &rgb3_str.truecolor(rgb3.0, rgb3.1, rgb3.2)));
         if i < 61 {
-            print!(
-                "{}\n",
-                &rgb4_str.truecolor(rgb4.0 as u8, rgb4.1 as u8, rgb4.2 as u8)
-            );
-        } else {
-            println!();
+            output.push_str(&format!("{}", &rgb4_str.truecolor(rgb4.0, rgb4.1, rgb4.2)));
         }
+        output.push('\n');
     }
+    output
+}
+
+pub fn print_index2rgb() {
+    let _ = Printer::stdout().write_str(&str_index2rgb());
 }
 
 pub fn error(message: &str, info: Option<&str>, err_msg: Option<&str>) -> String {
@@ -388,12 +335,12 @@ pub fn error(message: &str, info: Option<&str>, err_msg: Option<&str>) -> String
         ),
         (Some(info_str), None) => format!("{}: {}", basic_error, info_str.cerror()),
         (None, Some(err_str)) => format!("{}: {}", basic_error, err_str.cerror()),
-        (None, None) => format!("{}", basic_error),
+        (None, None) => basic_error.to_string(),
     }
 }
 
 pub fn print_error(message: &str, info: Option<&str>, err_msg: Option<&str>) {
-    println!("{}", error(message, info, err_msg));
+    let _ = Printer::stdout().error(message, info, err_msg);
 }
 
 pub fn warning(msg: &str) -> String {
@@ -401,7 +348,7 @@ pub fn warning(msg: &str) -> String {
 }
 
 pub fn print_warning(msg: &str) {
-    println!("{}", warning(msg));
+    let _ = Printer::stdout().warning(msg);
 }
 
 pub fn info(key: &str, value: &str) -> String {
@@ -409,17 +356,11 @@ pub fn info(key: &str, value: &str) -> String {
 }
 
 pub fn print_info(key: &str, value: &str) {
-    println!("{}", info(key, value));
+    let _ = Printer::stdout().info(key, value);
 }
 
-pub fn write_info(f: &mut fmt::Formatter, key: &str, value: &str) {
-    if let Err(e) = writeln!(f, "{}{}", key.cinfo(), value.cvar()) {
-        print_error(
-            "Error write",
-            Some(&"write_info".to_string()),
-            Some(&e.to_string()),
-        )
-    };
+pub fn write_info(f: &mut fmt::Formatter, key: &str, value: &str) -> fmt::Result {
+    writeln!(f, "{}", info(key, value))
 }
 
 pub fn message(txt: &str) -> String {
@@ -427,17 +368,11 @@ pub fn message(txt: &str) -> String {
 }
 
 pub fn print_message(txt: &str) {
-    println!("{}", message(txt));
+    let _ = Printer::stdout().message(txt);
 }
 
-pub fn write_message(f: &mut fmt::Formatter, txt: &str) {
-    if let Err(e) = writeln!(f, "{}", message(txt)) {
-        print_error(
-            "Error write",
-            Some(&"write_info".to_string()),
-            Some(&e.to_string()),
-        )
-    };
+pub fn write_message(f: &mut fmt::Formatter, txt: &str) -> fmt::Result {
+    writeln!(f, "{}", message(txt))
 }
 
 pub fn str_title(msg: &str) -> String {
@@ -445,30 +380,29 @@ pub fn str_title(msg: &str) -> String {
 }
 
 pub fn print_title(msg: &str) {
-    print!("{}", str_title(msg))
+    let _ = Printer::stdout().title(msg);
 }
-pub fn write_title(f: &mut fmt::Formatter, msg: &str) {
-    write!(f, "{}", str_title(msg)).unwrap()
+
+pub fn write_title(f: &mut fmt::Formatter, msg: &str) -> fmt::Result {
+    write!(f, "{}", str_title(msg))
 }
 
 pub fn write_header(
     f: &mut fmt::Formatter,
-    headers: &Vec<&str>,
-    widths: &Vec<usize>,
+    headers: &[&str],
+    widths: &[usize],
     start_index: usize,
     last_index: usize,
-) {
-    let output = make_header(headers, widths, start_index, last_index);
-    writeln!(f, "{}", output).unwrap()
+) -> fmt::Result {
+    writeln!(
+        f,
+        "{}",
+        make_header(headers, widths, start_index, last_index)
+    )
 }
 
-pub fn print_header(
-    headers: &Vec<&str>,
-    widths: &Vec<usize>,
-    start_index: usize,
-    last_index: usize,
-) {
-    println!("{}", make_header(headers, widths, start_index, last_index));
+pub fn print_header(headers: &[&str], widths: &[usize], start_index: usize, last_index: usize) {
+    let _ = Printer::stdout().header(headers, widths, start_index, last_index);
 }
 
 pub fn line(length: usize) -> String {
@@ -477,56 +411,49 @@ pub fn line(length: usize) -> String {
 }
 
 pub fn print_line(length: usize) {
-    print!("{}", line(length));
+    let _ = Printer::stdout().line(length);
 }
 
-pub fn write_line(f: &mut fmt::Formatter, length: usize) {
-    if let Err(e) = write!(f, "{}", line(length)) {
-        print_error(
-            "Error write",
-            Some(&"write_line".to_string()),
-            Some(&e.to_string()),
-        )
-    };
+pub fn write_line(f: &mut fmt::Formatter, length: usize) -> fmt::Result {
+    write!(f, "{}", line(length))
 }
 
 pub fn double_line(length: usize) -> String {
-    format!("{:═<length$}\n", "".cline())
+    format!("{}\n", "═".repeat(length).as_str().cline())
 }
 
 pub fn print_double_line(length: usize) {
-    print!("{}", double_line(length));
+    let _ = Printer::stdout().double_line(length);
 }
 
-pub fn write_double_line(f: &mut fmt::Formatter, length: usize) {
-    if let Err(e) = writeln!(f, "{}", double_line(length)) {
-        print_error(
-            "Error write",
-            Some(&"write_double_line".to_string()),
-            Some(&e.to_string()),
-        )
-    };
+pub fn write_double_line(f: &mut fmt::Formatter, length: usize) -> fmt::Result {
+    write!(f, "{}", double_line(length))
+}
+
+pub fn str_map<T: std::fmt::Display>(title: &str, val: &str, map: T) -> String {
+    format!(
+        "{}{}\n{}{}\n{}",
+        line(MEDIUM),
+        info(title, val),
+        line(MEDIUM),
+        map,
+        line(MEDIUM)
+    )
 }
 
 pub fn print_map<T: std::fmt::Display>(title: &str, val: &str, map: T) {
-    print_line(MEDIUM);
-    print_info(title, val);
-    print_line(MEDIUM);
-    println!("{}", map);
-    print_line(MEDIUM);
+    let _ = Printer::stdout().map(title, val, map);
 }
 
 pub fn str_key_value(key: &str, value: &str, max_klen: usize, max_vlen: usize) -> String {
-    let parts: Vec<String> = value
-        .chars()
-        .collect::<Vec<_>>()
-        .chunks(max_vlen)
-        .map(|chunk| chunk.iter().collect())
-        .collect();
+    let parts = split_width(value, max_vlen);
     let mut output: String = "".to_string();
     for (i, p) in parts.iter().enumerate() {
         if i == 0 {
-            output.push_str(&format!("{:max_klen$}: {}\n", key.cinfo(), p.cvar()));
+            // Padded before painting: a 256-color style carries its escape
+            // codes in the text, which `{:width$}` would count
+            let key = pad(key, max_klen);
+            output.push_str(&format!("{}: {}\n", key.cinfo(), p.cvar()));
         } else {
             output.push_str(&format!("{:max_klen$}  {}\n", " ", p.cvar()));
         }
@@ -546,26 +473,50 @@ pub fn str_vec(vec: &Vec<&str>, title: Option<&str>) -> String {
 }
 
 pub fn print_vec(vec: &Vec<&str>, title: Option<&str>) {
-    println!("\n{}", str_vec(vec, title));
+    let _ = Printer::stdout().vec(vec, title);
+}
+
+pub fn write_vec(f: &mut fmt::Formatter, vec: &Vec<&str>, title: Option<&str>) -> fmt::Result {
+    write!(f, "{}", str_vec(vec, title))
 }
 
 pub fn str_hashmap<K: std::fmt::Display, V: std::fmt::Display>(
     map: &HashMap<K, V>,
     title: Option<&str>,
 ) -> String {
+    let policy = redact_policy();
+    let entries: Vec<(String, String)> = map
+        .iter()
+        .map(|(key, value)| {
+            let key = key.to_string();
+            let value = policy.redact(&key, &value.to_string());
+            (key, value)
+        })
+        .collect();
+    str_entries(&entries, title)
+}
+
+// For maps whose values were already redacted when they were built, e.g. by
+// headers_to_hashmap: masking them again would mask the mask.
+pub(crate) fn str_redacted_hashmap(map: &HashMap<String, String>, title: Option<&str>) -> String {
+    let entries: Vec<(String, String)> = map.iter().map(|(k, v)| (k.clone(), v.clone())).collect();
+    str_entries(&entries, title)
+}
+
+fn str_entries(entries: &[(String, String)], title: Option<&str>) -> String {
     // let max_width = min(max_width, MAX_WIDTH);
     let max_width = get_terminal_width();
-    let max_k = map
-        .keys()
-        .map(|key| key.to_string().len())
+    let max_k = entries
+        .iter()
+        .map(|(key, _)| display_width(key))
         .max()
         .unwrap_or(max_width);
-    let mut max_v = map
-        .values()
-        .map(|val: &V| val.to_string().len())
+    let mut max_v = entries
+        .iter()
+        .map(|(_, val)| display_width(val))
         .max()
         .unwrap_or(max_width);
-    max_v = min(max_v, max_width - max_k - 3);
+    max_v = min(max_v, max_width.saturating_sub(max_k + 3)).max(1);
     let line_len = max_k + max_v + 3;
 
     let mut output = String::new();
@@ -575,13 +526,8 @@ pub fn str_hashmap<K: std::fmt::Display, V: std::fmt::Display>(
         output.push_str(&line(line_len));
     }
 
-    for (key, value) in map {
-        output.push_str(&str_key_value(
-            &key.to_string(),
-            &value.to_string(),
-            max_k,
-            max_v,
-        ));
+    for (key, value) in entries {
+        output.push_str(&str_key_value(key, value, max_k, max_v));
     }
     output.push_str(&line(line_len));
     output
@@ -591,15 +537,15 @@ pub fn print_hashmap<K: std::fmt::Display, V: std::fmt::Display>(
     map: &HashMap<K, V>,
     title: Option<&str>,
 ) {
-    print!("{}", str_hashmap(map, title));
+    let _ = Printer::stdout().hashmap(map, title);
 }
 
 pub fn write_hashmap<K: std::fmt::Display, V: std::fmt::Display>(
     f: &mut fmt::Formatter,
     map: &HashMap<K, V>,
     title: Option<&str>,
-) {
-    let _ = writeln!(f, "{}", str_hashmap(map, title));
+) -> fmt::Result {
+    writeln!(f, "{}", str_hashmap(map, title))
 }
 
 // In construction
@@ -609,19 +555,20 @@ pub fn str_struct<T: serde::Serialize>(title: &str, obj: &T) -> String {
     let mut max_k: usize = 0;
     let mut max_v: usize = 0;
 
-    let json = serde_json::to_value(obj).unwrap();
+    let mut json = serde_json::to_value(obj).unwrap();
+    redact_policy().redact_json(&mut json);
     let mut output = String::new();
 
     if let serde_json::Value::Object(map) = json {
-        max_k = max_k.max(map.keys().map(|key| key.len()).max().unwrap_or(0) + 1);
+        max_k = max_k.max(map.keys().map(|key| display_width(key)).max().unwrap_or(0) + 1);
         max_v = max_v.max(
             map.values()
-                .map(|val| val.to_string().len())
+                .map(|val| display_width(&val.to_string()))
                 .max()
                 .unwrap_or(0)
                 + 1,
         );
-        max_v = min(max_v, max_width - max_k - 3);
+        max_v = min(max_v, max_width.saturating_sub(max_k + 3)).max(1);
         let line_len = max_k + max_v + 3;
 
         // output.push_str(&line(line_len));
@@ -639,14 +586,14 @@ pub fn str_struct<T: serde::Serialize>(title: &str, obj: &T) -> String {
 }
 
 pub fn print_struct<T: serde::Serialize>(title: &str, obj: &T) {
-    println!("{}", str_struct(title, obj));
+    let _ = Printer::stdout().structure(title, obj);
 }
 
 pub fn write_struct<T: serde::Serialize>(
     f: &mut fmt::Formatter,
     title: &str,
     obj: &T,
-) -> Result<(), std::fmt::Error> {
+) -> fmt::Result {
     writeln!(f, "{}", str_struct(title, obj))
 }
 
@@ -669,18 +616,23 @@ fn get_values_from_value(value: &Value) -> Vec<String> {
     }
 }
 
-pub fn print_vec_struct<T: serde::Serialize>(title: &str, vec: &Vec<T>) {
+pub fn str_vec_struct<T: serde::Serialize>(title: &str, vec: &[T]) -> String {
     let mut table: Vec<Vec<&str>> = Vec::with_capacity(vec.len() + 1);
-    let obj1 = serde_json::to_value(vec.get(0)).expect("Failed to serialize struct");
+    let obj1 = serde_json::to_value(vec.first()).expect("Failed to serialize struct");
     table.push(get_keys_from_value(&obj1));
 
+    let policy = redact_policy();
     let rows_values = vec
         .iter()
-        .map(|v| serde_json::to_value(v).unwrap())
+        .map(|v| {
+            let mut value = serde_json::to_value(v).unwrap();
+            policy.redact_json(&mut value);
+            value
+        })
         .collect::<Vec<Value>>();
     let rows_string = rows_values
         .iter()
-        .map(|v| get_values_from_value(v))
+        .map(get_values_from_value)
         .collect::<Vec<Vec<String>>>();
     let rows_str = rows_string
         .iter()
@@ -689,46 +641,52 @@ pub fn print_vec_struct<T: serde::Serialize>(title: &str, vec: &Vec<T>) {
         .collect::<Vec<Vec<&str>>>();
 
     table.extend(rows_str);
-    println!();
-    print_title(&title);
-    println!();
-    print_table(table, true, None, Some(30));
+    format!(
+        "\n{}\n{}\n",
+        str_title(title),
+        make_table(table, true, None, Some(30))
+    )
 }
 
-pub fn print_start_program(program_name: &str) -> OffsetDateTime {
+pub fn print_vec_struct<T: serde::Serialize>(title: &str, vec: &[T]) {
+    let _ = Printer::stdout().vec_struct(title, vec);
+}
+
+pub fn str_start_program(program_name: &str, start: OffsetDateTime) -> String {
     let max_width = get_terminal_width();
-    let local = OffsetDateTime::now_utc();
-    let local_str: &str = &format!("{}", local);
-    println!("{RESET}\n");
-    print_double_line(max_width);
-    println!(
-        "{} {}: {}",
+    format!(
+        "{RESET}\n\n{}{} {}: {}\n{}\n\n",
+        double_line(max_width),
         "Start".cinfo(),
         program_name.ctitle(),
-        local_str.cvar()
-    );
-    print_double_line(max_width);
-    println!("\n");
-    local
+        start.to_string().cvar(),
+        double_line(max_width)
+    )
 }
 
-pub fn print_end_program(program_name: &str, start: OffsetDateTime) -> OffsetDateTime {
+pub fn print_start_program(program_name: &str) -> OffsetDateTime {
+    Printer::stdout()
+        .start_program(program_name)
+        .unwrap_or_else(|_| OffsetDateTime::now_utc())
+}
+
+pub fn str_end_program(program_name: &str, start: OffsetDateTime, end: OffsetDateTime) -> String {
     let max_width = get_terminal_width();
-    let local = OffsetDateTime::now_utc();
-    let local_str: &str = &format!("{}", local);
-    let duration_str: &str = &format!("{}", (local - start));
-    println!("{RESET}\n");
-    print_double_line(max_width);
-    println!(
-        "{} {}: {} - {}",
+    format!(
+        "{RESET}\n\n{}{} {}: {} - {}\n{}\n\n",
+        double_line(max_width),
         "End ".cinfo(),
         program_name.cinfo().bold(),
-        local_str.cvar(),
-        duration_str.cvar().bold()
-    );
-    print_double_line(max_width);
-    println!("\n");
-    local
+        end.to_string().cvar(),
+        (end - start).to_string().cvar().bold(),
+        double_line(max_width)
+    )
+}
+
+pub fn print_end_program(program_name: &str, start: OffsetDateTime) -> OffsetDateTime {
+    Printer::stdout()
+        .end_program(program_name, start)
+        .unwrap_or_else(|_| OffsetDateTime::now_utc())
 }
 
 pub enum TreeBlock {
@@ -736,26 +694,34 @@ pub enum TreeBlock {
     End,
 }
 
-pub fn print_tree_item(item: &str, block_type: TreeBlock) {
+pub fn tree_branch(block_type: &TreeBlock) -> ColoredString {
     match block_type {
-        TreeBlock::Item => println!("{} {}", "├──".cline(), item.citem()),
-        TreeBlock::End => println!("{} {}", "└──".cline(), item.citem()),
+        TreeBlock::Item => "├──".cline(),
+        TreeBlock::End => "└──".cline(),
     }
 }
 
+pub fn str_tree_item(item: &str, block_type: TreeBlock) -> String {
+    format!("{} {}", tree_branch(&block_type), item.citem())
+}
+
+pub fn print_tree_item(item: &str, block_type: TreeBlock) {
+    let _ = Printer::stdout().tree_item(item, block_type);
+}
+
 pub fn make_table(
     table: Vec<Vec<&str>>,
     header: bool,
     title: Option<&str>,
     column_width: Option<usize>,
 ) -> String {
-    let max_width = get_terminal_width() - 10;
+    let max_width = get_terminal_width().saturating_sub(10);
     let max_col_width = column_width.unwrap_or(MAX_COL_WIDTH);
 
     let title = title.map_or("".to_string(), |t| format!("{}{}", t.ctitle(), "\n"));
     let col_widths = get_column_widths(&table, max_col_width);
 
-    let mut output = String::from(title);
+    let mut output = title;
 
     let mut start_index = 0;
     while start_index < table[0].len() {
@@ -765,7 +731,7 @@ pub fn make_table(
         let line = format!("{}\n", "─".repeat(table_len).as_str().cline());
         for (i, row) in table.iter().enumerate() {
             if i == 0 {
-                if header == false {
+                if !header {
                     output.push_str(&line);
                 } else {
                     output.push_str(&make_header(row, &col_widths, start_index, next_index));
@@ -776,7 +742,7 @@ pub fn make_table(
             }
         }
         output.push_str(&line);
-        output.push_str("\n");
+        output.push('\n');
 
         start_index = next_index + 1;
     }
@@ -802,7 +768,7 @@ pub fn print_table(
     title: Option<&str>,
     column_width: Option<usize>,
 ) {
-    println!("{}", make_table(data, has_header, title, column_width));
+    let _ = Printer::stdout().table(data, has_header, title, column_width);
 }
 
 pub fn write_table(
@@ -811,67 +777,89 @@ pub fn write_table(
     has_header: bool,
     title: Option<&str>,
     column_width: Option<usize>,
-) -> Result<(), std::fmt::Error> {
+) -> fmt::Result {
     writeln!(f, "{}", make_table(data, has_header, title, column_width))
 }
 
+// Headers longer than their column wrap onto further header lines.
 pub fn make_header(
-    headers: &Vec<&str>,
-    widths: &Vec<usize>,
+    headers: &[&str],
+    widths: &[usize],
     start_index: usize,
     last_index: usize,
 ) -> String {
     let mut output = String::new();
-    let header_rows = (start_index..=last_index)
-        .map(|i| headers[i].len() / widths[i])
-        .max()
-        .unwrap_or(0);
+    let parts: Vec<Vec<String>> = (0..headers.len())
+        .map(|i| match i >= start_index && i <= last_index {
+            true => split_width(headers[i], widths[i]),
+            false => vec![],
+        })
+        .collect();
+    let header_rows = parts.iter().map(|p| p.len()).max().unwrap_or(0);
 
     for hr in 0..header_rows {
-        for (i, cell) in headers[start_index..=last_index].iter().enumerate() {
-            let padded = format!("{:width$}", cell, width = widths[i] * header_rows);
-            let slice = &padded[hr * widths[i]..widths[i] * (hr + 1)];
-            output.push_str(&format!("{}{}", slice.column(i), " ".repeat(SPACE)));
+        for (i, cell) in parts
+            .iter()
+            .enumerate()
+            .take(last_index + 1)
+            .skip(start_index)
+        {
+            let slice = cell.get(hr).map_or("", |s| s.as_str());
+            output.push_str(&format!(
+                "{}{}",
+                pad(slice, widths[i]).column(i),
+                " ".repeat(SPACE)
+            ));
         }
-        output.push_str("\n");
+        output.push('\n');
     }
     output
 }
 
-pub fn make_row(
-    row: &Vec<&str>,
-    widths: &Vec<usize>,
-    start_index: usize,
-    last_index: usize,
-) -> String {
+pub fn make_row(row: &[&str], widths: &[usize], start_index: usize, last_index: usize) -> String {
     let mut output = String::new();
-    for (i, cell) in row[start_index..=last_index].iter().enumerate() {
-        if cell.len() > widths[i] {
-            let truncated = &format!("{}*", &cell[..widths[i] - 1]);
-            output.push_str(&format!(
-                "{:width$}{}",
-                truncated.column(i),
-                " ".repeat(SPACE),
-                width = widths[i]
-            ));
+    for (i, cell) in row
+        .iter()
+        .enumerate()
+        .take(last_index + 1)
+        .skip(start_index)
+    {
+        let width = widths[i];
+        let text = if display_width(cell) > width {
+            let kept = split_width(cell, width.saturating_sub(1))
+                .into_iter()
+                .next()
+                .filter(|kept| display_width(kept) < width)
+                .unwrap_or_default();
+            format!("{}*", kept)
         } else {
-            output.push_str(&format!(
-                "{:width$}{}",
-                cell.column(i),
-                " ".repeat(SPACE),
-                width = widths[i]
-            ));
-        }
+            cell.to_string()
+        };
+        output.push_str(&format!(
+            "{}{}",
+            pad(&text, width).column(i),
+            " ".repeat(SPACE)
+        ));
     }
-    output.push_str("\n");
+    output.push('\n');
     output
 }
 
-fn next_columns_segment(start_index: usize, max_width: usize, col_widths: &Vec<usize>) -> usize {
+// Pads with spaces up to the visible width, escape sequences don't count.
+fn pad(s: &str, width: usize) -> String {
+    format!(
+        "{}{}",
+        s,
+        " ".repeat(width.saturating_sub(display_width(s)))
+    )
+}
+
+fn next_columns_segment(start_index: usize, max_width: usize, col_widths: &[usize]) -> usize {
     let mut current_width = 0;
     for (i, &width) in col_widths.iter().enumerate().skip(start_index) {
         current_width += width + SPACE;
-        if current_width > max_width {
+        // A segment holds at least one column, however narrow the terminal
+        if current_width > max_width && i > start_index {
             return i - 1;
         }
     }
@@ -882,23 +870,152 @@ pub fn get_column_widths(table: &Vec<Vec<&str>>, column_width: usize) -> Vec<usi
     let mut max_lengths: Vec<usize> = vec![0; table[0].len()];
     for row in table {
         for (i, cell) in row.iter().enumerate() {
-            max_lengths[i] = min(max_lengths[i].max(cell.len()), column_width);
+            max_lengths[i] = min(max_lengths[i].max(display_width(cell)), column_width);
         }
     }
     max_lengths
 }
 
-pub fn print_request(request: &Request) {
-    let req_map = request_to_hashmap(request);
-    print_hashmap(&req_map, Some("Request"));
+pub fn str_request<R: RequestParts>(request: &R) -> String {
+    str_redacted_hashmap(&request_to_hashmap(request), Some("Request"))
+}
+
+pub fn print_request<R: RequestParts>(request: &R) {
+    let _ = Printer::stdout().request(request);
+}
+
+pub fn str_response<M: Into<HttpMethod>, R: ResponseParts>(method: M, response: &R) -> String {
+    str_redacted_hashmap(&response_to_hashmap(method, response), Some("Response"))
+}
+
+pub fn print_response<M: Into<HttpMethod>, R: ResponseParts>(method: M, response: &R) {
+    let _ = Printer::stdout().response(method, response);
 }
 
-pub fn print_response(method: HttpMethod, response: &Response) {
-    let req_map = response_to_hashmap(method, response);
-    print_hashmap(&req_map, Some("Response"));
+pub fn str_headers(headers: &http::HeaderMap) -> String {
+    str_redacted_hashmap(&headers_to_hashmap(headers), Some("Headers"))
 }
 
-pub fn print_headers(headers: &reqwest::header::HeaderMap) {
-    let headers_map = headers_to_hashmap(headers);
-    print_hashmap(&headers_map, Some("Headers"));
+pub fn print_headers(headers: &http::HeaderMap) {
+    let _ = Printer::stdout().headers(headers);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use http::header::{HeaderMap, HeaderValue, AUTHORIZATION, SET_COOKIE};
+
+    use crate::request_ext::redacted_headers;
+
+    #[test]
+    fn ansi256_keys_are_padded() {
+        colored::control::set_override(true);
+        let output = Printer::new(Vec::new())
+            .theme(Theme::ansi256())
+            .render(|| str_key_value("a", "1", 10, 5) + &str_key_value("longer_key", "2", 10, 5));
+        let output = strip_ansi(&output);
+        let colons: Vec<Option<usize>> = output.lines().map(|l| l.find(':')).collect();
+        assert_eq!(colons, vec![Some(10), Some(10)], "{}", output);
+    }
+
+    #[test]
+    fn narrow_widths_do_not_panic() {
+        let mut map = HashMap::new();
+        map.insert("a_long_key_name", "value value value");
+        for width in [0, 5, 8] {
+            let mut printer = Printer::new(Vec::new()).width(width);
+            printer
+                .table(
+                    vec![vec!["col one", "col two"], vec!["a", "b"]],
+                    true,
+                    None,
+                    None,
+                )
+                .unwrap();
+            printer.hashmap(&map, Some("Map")).unwrap();
+            assert!(!printer.into_inner().is_empty());
+        }
+    }
+
+    #[test]
+    fn headers_are_redacted_once() {
+        let policy = RedactPolicy::default().reveal(Reveal::Partial(3, 4));
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            AUTHORIZATION,
+            HeaderValue::from_static("Bearer sk-0123456789a9f2"),
+        );
+        headers.append(
+            SET_COOKIE,
+            HeaderValue::from_static("session=0123456789abcd"),
+        );
+        headers.append(
+            SET_COOKIE,
+            HeaderValue::from_static("theme=dark-1234567890"),
+        );
+        let map = redacted_headers(&headers, &policy);
+        let output = strip_ansi(&str_redacted_hashmap(&map, Some("Headers")));
+
+        assert!(output.contains("Bearer sk-…a9f2"), "{}", output);
+        assert!(output.contains("ses…abcd | the…7890"), "{}", output);
+    }
+
+    // Display column at which `needle` starts in a line
+    fn column_of(line: &str, needle: &str) -> Option<usize> {
+        line.find(needle).map(|i| display_width(&line[..i]))
+    }
+
+    #[test]
+    fn wide_and_colored_cells_line_up() {
+        colored::control::set_override(true);
+        let output = Printer::new(Vec::new()).width(80).render(|| {
+            let red = "red".red().to_string();
+            make_table(
+                vec![
+                    vec!["name", "city"],
+                    vec!["東京タワー", "Tokyo"],
+                    vec![&red, "Paris"],
+                    vec!["plain", "Rome"],
+                ],
+                true,
+                None,
+                None,
+            )
+        });
+        let output = strip_ansi(&output);
+        let columns: Vec<Option<usize>> = ["city", "Tokyo", "Paris", "Rome"]
+            .iter()
+            .map(|city| output.lines().find_map(|l| column_of(l, city)))
+            .collect();
+        assert_eq!(columns, vec![Some(10 + SPACE); 4], "{}", output);
+    }
+
+    #[test]
+    fn wide_cells_are_truncated_by_width() {
+        let output = Printer::new(Vec::new()).width(80).render(|| {
+            make_table(
+                vec![vec!["name", "n"], vec!["東京タワー", "1"]],
+                true,
+                None,
+                Some(5),
+            )
+        });
+        let output = strip_ansi(&output);
+        let row = output.lines().find(|l| l.contains('*')).unwrap();
+        assert!(row.starts_with("東京*"), "{}", output);
+        assert_eq!(column_of(row, "1"), Some(5 + SPACE), "{}", output);
+    }
+
+    #[test]
+    fn wide_keys_and_values_line_up() {
+        let output =
+            strip_ansi(&(str_key_value("名前", "1", 6, 10) + &str_key_value("key", "2", 6, 10)));
+        let colons: Vec<Option<usize>> = output.lines().map(|l| column_of(l, ":")).collect();
+        assert_eq!(colons, vec![Some(6), Some(6)], "{}", output);
+
+        // Long values wrap at the value width, wide characters are not split
+        let output = strip_ansi(&str_key_value("k", "東京タワー", 1, 5));
+        let values: Vec<&str> = output.lines().map(|l| l[1..].trim()).collect();
+        assert_eq!(values, vec![": 東京", "タワ", "ー"], "{}", output);
+    }
 }
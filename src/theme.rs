@@ -0,0 +1,340 @@
+use std::cell::RefCell;
+use std::sync::{OnceLock, RwLock};
+
+use colored::{Color, ColoredString, Colorize};
+
+use crate::color::{
+    ansi16_color, detect_background, ensure_contrast, fg_code, palette, query_background,
+    rgb2ansi16, Background, ColorSupport,
+};
+use crate::printer::colors_enabled;
+
+static THEME: RwLock<Option<Theme>> = RwLock::new(None);
+// Default theme for the detected background, built on first use
+static DEFAULT_THEME: OnceLock<Theme> = OnceLock::new();
+
+// Column colors of the built-in themes, also exported as COLUMN_COLORS
+pub(crate) const BASIC_COLUMNS: [Color; 5] = [
+    Color::BrightBlue,
+    Color::BrightCyan,
+    Color::BrightMagenta,
+    Color::BrightGreen,
+    Color::BrightYellow,
+];
+pub(crate) const TRUECOLOR_COLUMNS: [(u8, u8, u8); 10] = [
+    (255, 0, 0),     // Red
+    (0, 255, 0),     // Green
+    (0, 0, 255),     // Blue
+    (255, 255, 0),   // Yellow
+    (0, 255, 255),   // Cyan
+    (255, 0, 255),   // Magenta
+    (192, 192, 192), // Silver
+    (128, 128, 128), // Gray
+    (128, 0, 0),     // Maroon
+    (128, 128, 0),   // Olive
+];
+pub(crate) const MONO_COLUMNS: [(u8, u8, u8); 3] = [(75, 75, 75), (150, 150, 150), (255, 255, 255)];
+
+thread_local! {
+    // Set while a Printer with its own theme renders a widget
+    static SCOPED_THEME: RefCell<Option<Theme>> = const { RefCell::new(None) };
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Style {
+    pub color: Option<Color>,
+    pub bold: bool,
+}
+
+impl Style {
+    pub const fn new(color: Color) -> Self {
+        Style {
+            color: Some(color),
+            bold: false,
+        }
+    }
+
+    pub const fn rgb(r: u8, g: u8, b: u8) -> Self {
+        Style::new(Color::TrueColor { r, g, b })
+    }
+
+    pub const fn plain() -> Self {
+        Style {
+            color: None,
+            bold: false,
+        }
+    }
+
+    pub const fn bold(mut self) -> Self {
+        self.bold = true;
+        self
+    }
+
+    // RGB colors get at least min_ratio contrast on the background; white
+    // and black swap on a light background.
+    pub fn adapt(mut self, background: Background, min_ratio: f64) -> Self {
+        self.color = self.color.map(|c| adapt_color(c, background, min_ratio));
+        self
+    }
+
+    pub fn paint(&self, s: &str) -> ColoredString {
+        self.paint_in(s, ColorSupport::TrueColor)
+    }
+
+    // RGB colors are reduced to the palette: real 38;5;N sequences for
+    // Ansi256, the nearest ANSI color for Basic. Named colors are kept.
+    pub fn paint_in(&self, s: &str, palette: ColorSupport) -> ColoredString {
+        let colored = match (self.color, palette) {
+            (Some(Color::TrueColor { r, g, b }), ColorSupport::Ansi256) => {
+                if colors_enabled() {
+                    ColoredString::from(format!("\x1b[{}m{}\x1b[0m", fg_code(r, g, b, palette), s))
+                } else {
+                    s.normal()
+                }
+            }
+            (Some(Color::TrueColor { r, g, b }), ColorSupport::Basic) => {
+                s.color(ansi16_color(rgb2ansi16(r, g, b)))
+            }
+            (Some(color), _) => s.color(color),
+            (None, _) => s.normal(),
+        };
+        if self.bold {
+            colored.bold()
+        } else {
+            colored
+        }
+    }
+}
+
+// Colors of the roles used by all widgets. The default follows the color
+// feature (basic, truecolors, colors256, mono); set_theme replaces it at runtime.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Theme {
+    pub info: Style,
+    pub var: Style,
+    pub title: Style,
+    pub error: Style,
+    pub warning: Style,
+    pub success: Style,
+    pub item: Style,
+    pub line: Style,
+    pub bullet: Style,
+    pub columns: Vec<Color>,
+    // Colors the RGB styles are reduced to when painted
+    pub palette: ColorSupport,
+}
+
+impl Theme {
+    pub fn basic() -> Self {
+        Theme {
+            info: Style::new(Color::Blue),
+            var: Style::new(Color::BrightCyan),
+            title: Style::new(Color::Blue).bold(),
+            error: Style::new(Color::BrightRed).bold(),
+            warning: Style::new(Color::BrightYellow),
+            success: Style::new(Color::BrightGreen),
+            item: Style::new(Color::BrightCyan),
+            line: Style::new(Color::BrightBlue).bold(),
+            bullet: Style::new(Color::BrightBlue).bold(),
+            columns: BASIC_COLUMNS.to_vec(),
+            palette: ColorSupport::TrueColor,
+        }
+    }
+
+    // basic for light backgrounds, avoiding the bright yellow and cyan
+    pub fn basic_light() -> Self {
+        Theme {
+            info: Style::new(Color::Blue),
+            var: Style::new(Color::Magenta),
+            title: Style::new(Color::Blue).bold(),
+            error: Style::new(Color::Red).bold(),
+            warning: Style::new(Color::Red),
+            success: Style::new(Color::Green),
+            item: Style::new(Color::Magenta),
+            line: Style::new(Color::Blue).bold(),
+            bullet: Style::new(Color::Blue).bold(),
+            columns: vec![
+                Color::Blue,
+                Color::Magenta,
+                Color::Red,
+                Color::Green,
+                Color::Black,
+            ],
+            palette: ColorSupport::TrueColor,
+        }
+    }
+
+    pub fn truecolor() -> Self {
+        Theme {
+            info: Style::rgb(51, 102, 255),
+            var: Style::rgb(0, 255, 255),
+            title: Style::rgb(51, 102, 255),
+            error: Style::rgb(255, 0, 0).bold(),
+            warning: Style::rgb(255, 255, 85),
+            success: Style::rgb(85, 255, 85),
+            item: Style::rgb(0, 255, 255),
+            line: Style::rgb(51, 102, 255),
+            bullet: Style::rgb(51, 102, 255),
+            columns: rgb_colors(&TRUECOLOR_COLUMNS),
+            palette: ColorSupport::TrueColor,
+        }
+    }
+
+    // The truecolor theme for terminals limited to the xterm 256 colors
+    pub fn ansi256() -> Self {
+        Theme {
+            palette: ColorSupport::Ansi256,
+            ..Theme::truecolor()
+        }
+    }
+
+    pub fn mono() -> Self {
+        Theme {
+            info: Style::new(Color::White),
+            var: Style::new(Color::White),
+            title: Style::new(Color::White),
+            error: Style::new(Color::White).bold(),
+            warning: Style::new(Color::White),
+            success: Style::new(Color::White),
+            item: Style::new(Color::White),
+            line: Style::new(Color::White),
+            bullet: Style::rgb(51, 102, 255),
+            columns: rgb_colors(&MONO_COLUMNS),
+            palette: ColorSupport::TrueColor,
+        }
+    }
+
+    // Keeps the theme readable on the background: text roles get a contrast
+    // of at least 4.5, column colors of 3.
+    pub fn adapt(mut self, background: Background) -> Self {
+        for style in [
+            &mut self.info,
+            &mut self.var,
+            &mut self.title,
+            &mut self.error,
+            &mut self.warning,
+            &mut self.success,
+            &mut self.item,
+            &mut self.line,
+            &mut self.bullet,
+        ] {
+            *style = style.adapt(background, 4.5);
+        }
+        for color in &mut self.columns {
+            *color = adapt_color(*color, background, 3.0);
+        }
+        self
+    }
+
+    pub fn palette(mut self, palette: ColorSupport) -> Self {
+        self.palette = palette;
+        self
+    }
+
+    pub fn paint(&self, style: Style, s: &str) -> ColoredString {
+        style.paint_in(s, self.palette)
+    }
+
+    // Replaces the column colors with n generated distinct ones, for tables
+    // and charts with more columns or series than the fixed list holds.
+    pub fn distinct_columns(mut self, n: usize, background: Background) -> Self {
+        self.columns = rgb_colors(&palette(n, background));
+        self
+    }
+
+    pub fn column(&self, index: usize) -> Style {
+        match self.columns.len() {
+            0 => Style::plain(),
+            n => Style::new(self.columns[index % n]),
+        }
+    }
+}
+
+// The feature's theme (basic, truecolors, colors256, mono) for a background
+impl Theme {
+    #[cfg(feature = "basic")]
+    pub fn for_background(background: Background) -> Self {
+        match background {
+            Background::Dark => Theme::basic(),
+            Background::Light => Theme::basic_light(),
+        }
+    }
+
+    #[cfg(feature = "truecolors")]
+    pub fn for_background(background: Background) -> Self {
+        Theme::truecolor().adapt(background)
+    }
+
+    #[cfg(feature = "colors256")]
+    pub fn for_background(background: Background) -> Self {
+        Theme::ansi256().adapt(background)
+    }
+
+    #[cfg(feature = "mono")]
+    pub fn for_background(background: Background) -> Self {
+        Theme::mono().adapt(background)
+    }
+}
+
+impl Theme {
+    // The feature's theme for the terminal's actual background, see
+    // query_background(). Use with set_theme(Theme::detect()).
+    pub fn detect() -> Self {
+        Theme::for_background(query_background())
+    }
+}
+
+// Follows detect_background(), which does not query the terminal
+impl Default for Theme {
+    fn default() -> Self {
+        Theme::for_background(detect_background())
+    }
+}
+
+fn adapt_color(color: Color, background: Background, min_ratio: f64) -> Color {
+    match (color, background) {
+        (Color::TrueColor { r, g, b }, _) => {
+            let (r, g, b) = ensure_contrast((r, g, b), background, min_ratio);
+            Color::TrueColor { r, g, b }
+        }
+        (Color::White, Background::Light) => Color::Black,
+        (Color::BrightWhite, Background::Light) => Color::BrightBlack,
+        (Color::Black, Background::Dark) => Color::White,
+        (Color::BrightBlack, Background::Dark) => Color::BrightWhite,
+        (color, _) => color,
+    }
+}
+
+fn rgb_colors(colors: &[(u8, u8, u8)]) -> Vec<Color> {
+    colors
+        .iter()
+        .map(|&(r, g, b)| Color::TrueColor { r, g, b })
+        .collect()
+}
+
+pub fn set_theme(theme: Theme) {
+    if let Ok(mut t) = THEME.write() {
+        *t = Some(theme);
+    }
+}
+
+pub fn theme() -> Theme {
+    with_theme(|t| t.clone())
+}
+
+// Runs f with the theme in effect on this thread: a Printer's own theme
+// while it renders, otherwise the global one.
+pub(crate) fn with_theme<R>(f: impl FnOnce(&Theme) -> R) -> R {
+    let scoped = SCOPED_THEME.with(|t| t.borrow().clone());
+    if let Some(theme) = scoped {
+        return f(&theme);
+    }
+    if let Some(theme) = THEME.read().ok().as_ref().and_then(|t| t.as_ref()) {
+        return f(theme);
+    }
+    f(DEFAULT_THEME.get_or_init(Theme::default))
+}
+
+pub(crate) fn swap_scoped_theme(theme: Option<Theme>) -> Option<Theme> {
+    SCOPED_THEME.with(|t| t.replace(theme))
+}
@@ -0,0 +1,225 @@
+use std::fmt;
+use std::io::{self, Write};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use tracing_core::field::{Field, Visit};
+use tracing_core::span::{Attributes, Id, Record};
+use tracing_core::{Event, Subscriber};
+use tracing_subscriber::layer::{Context, Layer};
+use tracing_subscriber::registry::LookupSpan;
+
+use crate::logger::{log_config, str_log, Level, LogConfig};
+use crate::printer::Printer;
+use crate::termprint::{str_key_value, str_tree_item, tree_branch, ColoredItem, TreeBlock};
+
+const MESSAGE_FIELD: &str = "message";
+const MAX_FIELD_WIDTH: usize = 60;
+
+impl From<&tracing_core::Level> for Level {
+    fn from(level: &tracing_core::Level) -> Self {
+        match *level {
+            tracing_core::Level::TRACE => Level::Trace,
+            tracing_core::Level::DEBUG => Level::Debug,
+            tracing_core::Level::INFO => Level::Info,
+            tracing_core::Level::WARN => Level::Warn,
+            tracing_core::Level::ERROR => Level::Error,
+        }
+    }
+}
+
+#[derive(Default)]
+struct FieldVisitor {
+    message: Option<String>,
+    fields: Vec<(String, String)>,
+}
+
+impl Visit for FieldVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == MESSAGE_FIELD {
+            self.message = Some(value.to_string());
+        } else {
+            self.fields
+                .push((field.name().to_string(), value.to_string()));
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == MESSAGE_FIELD {
+            self.message = Some(format!("{:?}", value));
+        } else {
+            self.fields
+                .push((field.name().to_string(), format!("{:?}", value)));
+        }
+    }
+}
+
+// Stored in the span's extensions while it is open.
+struct SpanState {
+    depth: usize,
+    opened: Instant,
+}
+
+// tracing_subscriber Layer that prints spans as a tree: a span opens a branch,
+// its events and child spans are indented below it and the closing line
+// reports how long the span was open. Filtering uses the LogConfig filter.
+// Output goes to stdout or the Printer given with `printer`.
+pub struct TermprintLayer<W: Write = io::Stdout> {
+    config: LogConfig,
+    printer: Arc<Mutex<Printer<W>>>,
+}
+
+impl Default for TermprintLayer {
+    fn default() -> Self {
+        TermprintLayer::new()
+    }
+}
+
+impl TermprintLayer {
+    pub fn new() -> Self {
+        TermprintLayer {
+            config: log_config(),
+            printer: Arc::new(Mutex::new(Printer::stdout())),
+        }
+    }
+}
+
+impl<W: Write> TermprintLayer<W> {
+    pub fn config(mut self, config: LogConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    // Output goes to this printer instead of stdout, e.g. a shared
+    // Printer::new(Vec::new()) to capture it
+    pub fn printer<V: Write>(self, printer: Arc<Mutex<Printer<V>>>) -> TermprintLayer<V> {
+        TermprintLayer {
+            config: self.config,
+            printer,
+        }
+    }
+
+    // Renders with the printer's theme, width and color policy.
+    fn write<F: FnOnce() -> String>(&self, f: F) {
+        if let Ok(mut printer) = self.printer.lock() {
+            let output = printer.render(f);
+            let _ = printer.write_str(&output);
+        }
+    }
+}
+
+fn indent(depth: usize) -> String {
+    "│   ".repeat(depth).as_str().cline().to_string()
+}
+
+// Key/value lines aligned like str_key_value, shifted under the tree branch.
+fn str_fields(fields: &[(String, String)], depth: usize) -> String {
+    let max_k = fields.iter().map(|(k, _)| k.len()).max().unwrap_or(0);
+    let prefix = format!("{}    ", indent(depth));
+    fields
+        .iter()
+        .flat_map(|(k, v)| {
+            str_key_value(k, v, max_k, MAX_FIELD_WIDTH)
+                .lines()
+                .map(|l| format!("{}{}\n", prefix, l))
+                .collect::<Vec<String>>()
+        })
+        .collect()
+}
+
+impl<S, W> Layer<S> for TermprintLayer<W>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    W: Write + Send + 'static,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let metadata = span.metadata();
+        if !self
+            .config
+            .enabled(Level::from(metadata.level()), Some(metadata.target()))
+        {
+            return;
+        }
+        let depth = span
+            .scope()
+            .skip(1)
+            .filter(|s| s.extensions().get::<SpanState>().is_some())
+            .count();
+        let mut visitor = FieldVisitor::default();
+        attrs.record(&mut visitor);
+
+        self.write(|| {
+            format!(
+                "{}{}\n{}",
+                indent(depth),
+                str_tree_item(metadata.name(), TreeBlock::Item),
+                str_fields(&visitor.fields, depth + 1)
+            )
+        });
+        span.extensions_mut().insert(SpanState {
+            depth,
+            opened: Instant::now(),
+        });
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let Some(depth) = span.extensions().get::<SpanState>().map(|s| s.depth) else {
+            return;
+        };
+        let mut visitor = FieldVisitor::default();
+        values.record(&mut visitor);
+        self.write(|| str_fields(&visitor.fields, depth + 1));
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let metadata = event.metadata();
+        let level = Level::from(metadata.level());
+        if !self.config.enabled(level, Some(metadata.target())) {
+            return;
+        }
+        let depth = ctx
+            .event_scope(event)
+            .and_then(|mut scope| {
+                scope.find_map(|s| s.extensions().get::<SpanState>().map(|st| st.depth + 1))
+            })
+            .unwrap_or(0);
+        let mut visitor = FieldVisitor::default();
+        event.record(&mut visitor);
+
+        let msg = visitor.message.unwrap_or_default();
+        self.write(|| {
+            format!(
+                "{}{} {}\n{}",
+                indent(depth),
+                tree_branch(&TreeBlock::Item),
+                str_log(&self.config, level, Some(metadata.target()), &msg),
+                str_fields(&visitor.fields, depth + 1)
+            )
+        });
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(&id) else {
+            return;
+        };
+        let extensions = span.extensions();
+        let Some(state) = extensions.get::<SpanState>() else {
+            return;
+        };
+        let elapsed = format!("{:.2?}", state.opened.elapsed());
+        self.write(|| {
+            format!(
+                "{}{} {}\n",
+                indent(state.depth + 1),
+                str_tree_item(span.name(), TreeBlock::End),
+                elapsed.as_str().cvar()
+            )
+        });
+    }
+}
@@ -0,0 +1,93 @@
+#![cfg(feature = "middleware")]
+
+use std::io::{Read, Write};
+use std::net::TcpListener;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use termprint::{ColorPolicy, PrintMiddleware, Printer, Verbosity};
+
+const BODY: &str = r#"{"id":1,"name":"widget"}"#;
+
+// Answers a single request with a JSON body, returns the URL to call.
+fn serve_once() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut request = Vec::new();
+        let mut buf = [0u8; 1024];
+        while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+            match stream.read(&mut buf) {
+                Ok(0) | Err(_) => break,
+                Ok(n) => request.extend_from_slice(&buf[..n]),
+            }
+        }
+        let response = format!(
+            "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\nx-request-id: abc123\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+            BODY.len(),
+            BODY
+        );
+        let _ = stream.write_all(response.as_bytes());
+    });
+    format!("http://{}/items?token=secret123", addr)
+}
+
+fn client<W: Write + Send + 'static>(
+    middleware: PrintMiddleware<W>,
+) -> reqwest_middleware::ClientWithMiddleware {
+    let client = reqwest::Client::builder().no_proxy().build().unwrap();
+    reqwest_middleware::ClientBuilder::new(client)
+        .with(middleware)
+        .build()
+}
+
+fn captured(printer: &Mutex<Printer<Vec<u8>>>) -> String {
+    String::from_utf8(printer.lock().unwrap().get_ref().clone()).unwrap()
+}
+
+#[tokio::test]
+async fn bodies_are_printed_to_the_printer() {
+    let url = serve_once();
+    let printer = Arc::new(Mutex::new(
+        Printer::new(Vec::new())
+            .width(100)
+            .color(ColorPolicy::Never),
+    ));
+    let client = client(PrintMiddleware::new(Verbosity::Bodies).printer(printer.clone()));
+
+    let response = client
+        .get(&url)
+        .header("authorization", "Bearer sk-0123456789abcdef")
+        .send()
+        .await
+        .unwrap();
+    // The rebuilt response keeps what callers read from it
+    assert_eq!(response.url().as_str(), url);
+    assert_eq!(response.headers()["x-request-id"], "abc123");
+    assert_eq!(response.text().await.unwrap(), BODY);
+
+    let output = captured(&printer);
+    assert!(output.contains("GET"), "{}", output);
+    assert!(output.contains("x-request-id"), "{}", output);
+    assert!(output.contains("widget"), "{}", output);
+    assert!(!output.contains("sk-0123456789abcdef"), "{}", output);
+    assert!(!output.contains("secret123"), "{}", output);
+    assert!(!output.contains('\x1b'), "{}", output);
+}
+
+#[tokio::test]
+async fn status_line_is_printed_to_the_printer() {
+    let url = serve_once();
+    let printer = Arc::new(Mutex::new(
+        Printer::new(Vec::new()).color(ColorPolicy::Never),
+    ));
+    let client = client(PrintMiddleware::new(Verbosity::Status).printer(printer.clone()));
+
+    let response = client.get(&url).send().await.unwrap();
+    assert_eq!(response.text().await.unwrap(), BODY);
+
+    let output = captured(&printer);
+    assert_eq!(output.lines().count(), 1, "{}", output);
+    assert!(output.contains("200"), "{}", output);
+}
@@ -0,0 +1,52 @@
+#![cfg(feature = "tracing")]
+
+use std::sync::{Arc, Mutex};
+
+use termprint::{ColorPolicy, Level, LogConfig, Printer, TermprintLayer};
+use tracing_subscriber::layer::SubscriberExt;
+
+fn captured(printer: &Mutex<Printer<Vec<u8>>>) -> String {
+    String::from_utf8(printer.lock().unwrap().get_ref().clone()).unwrap()
+}
+
+#[test]
+fn spans_and_events_are_printed_to_the_printer() {
+    let printer = Arc::new(Mutex::new(
+        Printer::new(Vec::new()).color(ColorPolicy::Never),
+    ));
+    let layer = TermprintLayer::new()
+        .config(LogConfig::default().min_level(Level::Info).no_timestamp())
+        .printer(printer.clone());
+    let subscriber = tracing_subscriber::registry().with(layer);
+
+    tracing::subscriber::with_default(subscriber, || {
+        let span = tracing::info_span!("fetch", url = "https://example.com");
+        let _guard = span.enter();
+        tracing::info!(items = 3, "loaded");
+        tracing::debug!("filtered out");
+    });
+
+    let output = captured(&printer);
+    let lines: Vec<&str> = output.lines().collect();
+    assert!(lines[0].contains("fetch"), "{}", output);
+    assert!(
+        lines[1].contains("url") && lines[1].contains("https://example.com"),
+        "{}",
+        output
+    );
+    assert!(
+        lines[2].contains("INFO") && lines[2].contains("loaded"),
+        "{}",
+        output
+    );
+    assert!(
+        lines[3].contains("items") && lines[3].contains('3'),
+        "{}",
+        output
+    );
+    // Closing line of the span
+    assert_eq!(lines.len(), 5, "{}", output);
+    assert!(lines[4].contains("fetch"), "{}", output);
+    assert!(!output.contains("filtered out"), "{}", output);
+    assert!(!output.contains('\x1b'), "{}", output);
+}